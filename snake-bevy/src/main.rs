@@ -1,6 +1,7 @@
 const DOUBLE_TAP_TIMEOUT: Duration = Duration::from_millis(500);
 
 use std::collections::BTreeMap;
+use std::f32::consts::FRAC_PI_2;
 use web_time::Duration;
 
 use bevy::{
@@ -8,7 +9,12 @@ use bevy::{
     platform::time::Instant, prelude::*, sprite::Text2dShadow,
 };
 
-use snake_game::{game_with_menu::GameWithMenu, traits::DrawableOn};
+use snake_game::{
+    clock::SystemClock,
+    game_with_menu::GameWithMenu,
+    layout::LINE_HEIGHT,
+    traits::{DrawableOn, Rgba},
+};
 #[derive(Debug, bevy::prelude::Resource, Default)]
 struct GameWithMenuResource(GameWithMenu);
 
@@ -124,6 +130,14 @@ impl TouchPositions {
 }
 
 fn main() {
+    let mut game_with_menu = GameWithMenu::from_args(std::env::args().skip(1), SystemClock)
+        .unwrap_or_else(|error| {
+            eprintln!("{error}");
+            std::process::exit(2)
+        });
+    for warning in game_with_menu.take_warnings() {
+        eprintln!("{warning}");
+    }
     let mut app = App::new();
     app.add_plugins(DefaultPlugins.set(WindowPlugin {
         primary_window: Some(Window {
@@ -134,7 +148,7 @@ fn main() {
         ..default()
     }));
 
-    app.init_resource::<GameWithMenuResource>();
+    app.insert_resource(GameWithMenuResource(game_with_menu));
 
     app.init_resource::<TouchPositions>();
     app.init_resource::<Entities>();
@@ -181,6 +195,13 @@ fn main() {
 }
 const X_EXTENT: u32 = 1000;
 const Y_EXTENT: u32 = 600;
+/// How much closer to the camera every drawn rectangle or text is than the one before it.
+const DEPTH_STEP: f32 = 0.01;
+/// How many straight pieces every corner of a rounded rectangle is made of.
+const CORNER_SEGMENTS: usize = 6;
+/// Sprites are loaded from `NAME.png` in this directory of bevy's asset folder, which is
+/// `assets` next to the executable unless `BEVY_ASSET_ROOT` says otherwise.
+const SPRITE_DIR: &str = "sprites";
 
 fn setup(mut commands: Commands) {
     let projection = Projection::Orthographic(OrthographicProjection {
@@ -218,17 +239,28 @@ fn snake_down_pressed(mut game_with_menu: ResMut<GameWithMenuResource>) {
 struct Entities {
     used_rects: Vec<Entity>,
     unused_rects: Vec<Entity>,
-    materials: BTreeMap<(u8, u8, u8), AssetId<ColorMaterial>>,
+    materials: BTreeMap<Rgba, AssetId<ColorMaterial>>,
     mesh_map: BTreeMap<(usize, usize), AssetId<Mesh>>,
+    circle_mesh: Option<AssetId<Mesh>>,
+    /// Rounded rectangles by their size and their radius in whole pixels.
+    rounded_meshes: BTreeMap<((usize, usize), usize), AssetId<Mesh>>,
     unused_text: Vec<Entity>,
     used_text: Vec<Entity>,
+    /// The images of the sprites by name, including the ones that failed to load.
+    sprites: BTreeMap<String, Handle<Image>>,
+    unused_sprites: Vec<Entity>,
+    used_sprites: Vec<Entity>,
 }
 
-struct Frame<'a, 'b, 'c, 'd, 'e, 'f, 'g, 'h, 'i, 'j> {
+/// Sprites are the only drawn entities without a text or a mesh.
+type SpriteFilter = (Without<Text2d>, Without<Mesh2d>);
+
+struct Frame<'a, 'b, 'c, 'd, 'e, 'f, 'g, 'h, 'i, 'j, 'k, 'l, 'm, 'n> {
     commands: Commands<'a, 'b>,
     meshes: ResMut<'a, Assets<Mesh>>,
     materials: ResMut<'a, Assets<ColorMaterial>>,
     entities: ResMut<'a, Entities>,
+    asset_server: Res<'a, AssetServer>,
     rect_query: Query<
         'a,
         'b,
@@ -248,17 +280,29 @@ struct Frame<'a, 'b, 'c, 'd, 'e, 'f, 'g, 'h, 'i, 'j> {
             &'g mut Visibility,
             &'h mut TextColor,
             &'i mut Text2d,
+            &'k mut TextFont,
         ),
         Without<MeshMaterial2d<ColorMaterial>>,
     >,
+    sprite_query:
+        Query<'a, 'b, (&'l mut Transform, &'m mut Visibility, &'n mut Sprite), SpriteFilter>,
+    /// The z of the next thing drawn, so everything is drawn over what was drawn before it.
+    depth: f32,
+}
+
+impl Frame<'_, '_, '_, '_, '_, '_, '_, '_, '_, '_, '_, '_, '_, '_> {
+    fn next_depth(&mut self) -> f32 {
+        self.depth += DEPTH_STEP;
+        self.depth
+    }
 }
 
-impl DrawableOn for Frame<'_, '_, '_, '_, '_, '_, '_, '_, '_, '_> {
+impl DrawableOn for Frame<'_, '_, '_, '_, '_, '_, '_, '_, '_, '_, '_, '_, '_, '_> {
     fn draw_text(&mut self, text: &str, color_rgb: (u8, u8, u8), x: usize, y: usize, size: f32) {
         let text_x = x as f32 - (X_EXTENT / 2) as f32;
         let text_y = -(y as f32) + (Y_EXTENT / 2) as f32;
 
-        let text_z = 0f32;
+        let text_z = self.next_depth();
         let text_color = TextColor(Color::linear_rgb(
             color_rgb.0 as f32 / 255.,
             color_rgb.1 as f32 / 255.,
@@ -267,24 +311,25 @@ impl DrawableOn for Frame<'_, '_, '_, '_, '_, '_, '_, '_, '_, '_> {
 
         match self.entities.unused_text.pop() {
             Some(t) => {
-                let (mut transform, mut vis, mut color, mut text_comp) =
+                let (mut transform, mut vis, mut color, mut text_comp, mut text_font) =
                     self.text_query.get_mut(t).expect("Cannot fail");
                 transform.translation = Vec3::new(text_x as f32, text_y as f32, text_z);
                 *vis = Visibility::Visible;
                 *color = text_color;
                 text_comp.0 = text.to_string();
+                text_font.font_size = size / LINE_HEIGHT;
                 self.entities.used_text.push(t);
             }
             None => {
                 let text_font: TextFont = TextFont {
-                    font_size: size / 1.2,
+                    font_size: size / LINE_HEIGHT,
                     ..Default::default()
                 };
 
                 let text_entity = self.commands.spawn((
                     Text2d::new(text.to_string()),
                     text_font,
-                    Transform::from_xyz(0., 100., 0.),
+                    Transform::from_xyz(text_x, text_y, text_z),
                     Text2dShadow::default(),
                     text_color,
                 ));
@@ -295,7 +340,7 @@ impl DrawableOn for Frame<'_, '_, '_, '_, '_, '_, '_, '_, '_, '_> {
         //   Going over all leftover text and then doing the following:
         self.entities.unused_text.iter().for_each(|entity| {
             //   Destructuring queries entity
-            let (_transform, mut vis, _, _text) =
+            let (_transform, mut vis, _, _text, _) =
                 self.text_query.get_mut(*entity).expect("Cannot fail");
             //   Changing their visibility to invisible
             *vis = Visibility::Hidden;
@@ -315,65 +360,237 @@ impl DrawableOn for Frame<'_, '_, '_, '_, '_, '_, '_, '_, '_, '_> {
         color_rgb: (u8, u8, u8),
         top_left: (usize, usize),
     ) {
-        // let game_top_left = (-775f32, 465f32);
-        //   Position xyz for rectangle
-        let rect_x = (top_left.0 as f32) - ((X_EXTENT) / 2) as f32 + size.0 as f32 / 2.;
-        let rect_y = -(top_left.1 as f32) + ((Y_EXTENT) / 2) as f32 - size.1 as f32 / 2.;
-        let rect_z = 0.;
-        //   This takes the color id from the btreemap and inserts the id if it is not there.
-        let color_id = self.entities.materials.entry(color_rgb).or_insert(
-            self.materials
-                .add(Color::linear_rgb(
-                    color_rgb.0 as f32 / 255.,
-                    color_rgb.1 as f32 / 255.,
-                    color_rgb.2 as f32 / 255.,
-                ))
-                .id(),
-        );
+        self.fill_rectangle_rgba(size, (color_rgb.0, color_rgb.1, color_rgb.2, 255), top_left);
+    }
 
-        let color_material_handle = self
-            .materials
-            .get_strong_handle(*color_id)
-            .expect("Cannot fail");
+    fn fill_rectangle_rgba(
+        &mut self,
+        size: (usize, usize),
+        color_rgba: Rgba,
+        top_left: (usize, usize),
+    ) {
+        //   Position of the center of the rectangle
+        let (rect_x, rect_y) = to_world((
+            top_left.0 as f32 + size.0 as f32 / 2.,
+            top_left.1 as f32 + size.1 as f32 / 2.,
+        ));
+        let rect_z = self.next_depth();
 
-        let rectangle_id = self.entities.mesh_map.entry(size).or_insert(
+        let rectangle_id = *self.entities.mesh_map.entry(size).or_insert_with(|| {
             self.meshes
                 .add(Rectangle::new(size.0 as f32, size.1 as f32))
-                .id(),
+                .id()
+        });
+
+        self.draw_mesh(
+            rectangle_id,
+            color_rgba,
+            Transform::from_xyz(rect_x, rect_y, rect_z),
+        );
+    }
+
+    fn fill_circle(&mut self, center: (f32, f32), radius: f32, color_rgba: Rgba) {
+        let (circle_x, circle_y) = to_world(center);
+        let circle_z = self.next_depth();
+
+        //   All circles share one mesh with a radius of 1 that is scaled to their radius.
+        let circle_id = *self
+            .entities
+            .circle_mesh
+            .get_or_insert_with(|| self.meshes.add(Circle::new(1.)).id());
+
+        self.draw_mesh(
+            circle_id,
+            color_rgba,
+            Transform::from_xyz(circle_x, circle_y, circle_z)
+                .with_scale(Vec3::new(radius, radius, 1.)),
+        );
+    }
+
+    fn fill_rounded_rectangle(
+        &mut self,
+        size: (usize, usize),
+        radius: f32,
+        color_rgba: Rgba,
+        top_left: (usize, usize),
+    ) {
+        let radius = radius
+            .min(size.0 as f32 / 2.)
+            .min(size.1 as f32 / 2.)
+            .round() as usize;
+        if radius == 0 {
+            return self.fill_rectangle_rgba(size, color_rgba, top_left);
+        }
+        let (rect_x, rect_y) = to_world((
+            top_left.0 as f32 + size.0 as f32 / 2.,
+            top_left.1 as f32 + size.1 as f32 / 2.,
+        ));
+        let rect_z = self.next_depth();
+
+        let rounded_id = *self
+            .entities
+            .rounded_meshes
+            .entry((size, radius))
+            .or_insert_with(|| self.meshes.add(rounded_rectangle(size, radius as f32)).id());
+
+        self.draw_mesh(
+            rounded_id,
+            color_rgba,
+            Transform::from_xyz(rect_x, rect_y, rect_z),
         );
+    }
 
-        let rectangle = self
-            .meshes
-            .get_strong_handle(*rectangle_id)
+    fn draw_line(&mut self, from: (f32, f32), to: (f32, f32), width: f32, color_rgba: Rgba) {
+        let (from_x, from_y) = to_world(from);
+        let (to_x, to_y) = to_world(to);
+        let length = (to_x - from_x).hypot(to_y - from_y);
+        let line_z = self.next_depth();
+
+        //   A square of 1 by 1 stretched to the line and turned in its direction.
+        let square_id = *self
+            .entities
+            .mesh_map
+            .entry((1, 1))
+            .or_insert_with(|| self.meshes.add(Rectangle::new(1., 1.)).id());
+
+        self.draw_mesh(
+            square_id,
+            color_rgba,
+            Transform::from_xyz((from_x + to_x) / 2., (from_y + to_y) / 2., line_z)
+                .with_rotation(Quat::from_rotation_z((to_y - from_y).atan2(to_x - from_x)))
+                .with_scale(Vec3::new(length, width, 1.)),
+        );
+    }
+
+    fn draw_sprite(
+        &mut self,
+        name: &str,
+        size: (usize, usize),
+        top_left: (usize, usize),
+        fallback_rgba: Rgba,
+    ) {
+        //   The image is only requested once, a missing file just never finishes loading.
+        let image = self
+            .entities
+            .sprites
+            .entry(name.to_string())
+            .or_insert_with(|| self.asset_server.load(format!("{SPRITE_DIR}/{name}.png")))
+            .clone();
+        if !self.asset_server.is_loaded(&image) {
+            return self.fill_rectangle_rgba(size, fallback_rgba, top_left);
+        }
+
+        let (sprite_x, sprite_y) = to_world((
+            top_left.0 as f32 + size.0 as f32 / 2.,
+            top_left.1 as f32 + size.1 as f32 / 2.,
+        ));
+        let transform = Transform::from_xyz(sprite_x, sprite_y, self.next_depth());
+        let custom_size = Some(Vec2::new(size.0 as f32, size.1 as f32));
+
+        match self.entities.unused_sprites.pop() {
+            Some(entity) => {
+                let (mut old_transform, mut vis, mut sprite) =
+                    self.sprite_query.get_mut(entity).expect("Cannot fail");
+                *old_transform = transform;
+                *vis = Visibility::Visible;
+                sprite.image = image;
+                sprite.custom_size = custom_size;
+                self.entities.used_sprites.push(entity);
+            }
+            None => {
+                let sprite_entity = self.commands.spawn((
+                    Sprite {
+                        image,
+                        custom_size,
+                        ..default()
+                    },
+                    transform,
+                ));
+                self.entities.used_sprites.push(sprite_entity.id());
+            }
+        }
+    }
+}
+
+/// A rectangle of `size` centered on the origin, whose corners are quarter circles of `radius`.
+fn rounded_rectangle(size: (usize, usize), radius: f32) -> ConvexPolygon {
+    let (half_width, half_height) = (size.0 as f32 / 2. - radius, size.1 as f32 / 2. - radius);
+    let corners = [
+        (half_width, half_height),
+        (-half_width, half_height),
+        (-half_width, -half_height),
+        (half_width, -half_height),
+    ];
+    //   Counterclockwise from the right end of the top right corner.
+    let vertices = corners.iter().enumerate().flat_map(|(corner, &(x, y))| {
+        (0..=CORNER_SEGMENTS).map(move |step| {
+            let angle =
+                (corner * CORNER_SEGMENTS + step) as f32 * FRAC_PI_2 / CORNER_SEGMENTS as f32;
+            Vec2::new(x + radius * angle.cos(), y + radius * angle.sin())
+        })
+    });
+    ConvexPolygon::new_unchecked(vertices)
+}
+
+/// Converts a position on the frame, from the top left corner, to the world, from the center with y going up.
+fn to_world((x, y): (f32, f32)) -> (f32, f32) {
+    (x - (X_EXTENT / 2) as f32, -y + (Y_EXTENT / 2) as f32)
+}
+
+impl Frame<'_, '_, '_, '_, '_, '_, '_, '_, '_, '_, '_, '_, '_, '_> {
+    /// Draws `mesh_id` in `color_rgba` with `transform`, on a hidden entity from the last frame if there is one.
+    fn draw_mesh(&mut self, mesh_id: AssetId<Mesh>, color_rgba: Rgba, transform: Transform) {
+        //   This takes the color id from the btreemap and inserts the id if it is not there.
+        //   See-through colors get a material that blends.
+        let color_id = *self
+            .entities
+            .materials
+            .entry(color_rgba)
+            .or_insert_with(|| {
+                self.materials
+                    .add(Color::linear_rgba(
+                        color_rgba.0 as f32 / 255.,
+                        color_rgba.1 as f32 / 255.,
+                        color_rgba.2 as f32 / 255.,
+                        color_rgba.3 as f32 / 255.,
+                    ))
+                    .id()
+            });
+
+        let color_material_handle = self
+            .materials
+            .get_strong_handle(color_id)
             .expect("Cannot fail");
 
+        let mesh_handle = self.meshes.get_strong_handle(mesh_id).expect("Cannot fail");
+
         match self.entities.unused_rects.pop() {
             Some(rect) => {
-                let (mut transform, mut vis, mut color, mut mesh) =
+                let (mut old_transform, mut vis, mut color, mut mesh) =
                     self.rect_query.get_mut(rect).expect("Cannot fail");
-                //   Changing the position to the desired position (See rect_x/y/z)
-                transform.translation = Vec3::new(rect_x as f32, rect_y as f32, rect_z);
-                //   Making the rectangle visible
+                //   Changing the position, size and rotation to the desired ones
+                *old_transform = transform;
+                //   Making the mesh visible
                 *vis = Visibility::Visible;
-                *mesh = Mesh2d(rectangle);
+                *mesh = Mesh2d(mesh_handle);
                 //   Changing the color the color_material_handle color
                 *color = MeshMaterial2d(color_material_handle);
-                //   Adding the drawn rectangle to the used list
+                //   Adding the drawn mesh to the used list
                 self.entities.used_rects.push(rect);
             }
             None => {
-                let rectangle_entity = self.commands.spawn((
+                let mesh_entity = self.commands.spawn((
                     MeshMaterial2d(color_material_handle),
-                    Transform::from_xyz(rect_x as f32, rect_y as f32, rect_z),
-                    Mesh2d(rectangle),
+                    transform,
+                    Mesh2d(mesh_handle),
                 ));
 
-                //   Adding the drawn rectangle to th>e used list
-                self.entities.used_rects.push(rectangle_entity.id());
+                //   Adding the drawn mesh to the used list
+                self.entities.used_rects.push(mesh_entity.id());
             }
         }
 
-        //   Going over all leftover rectangles and then doing the following:
+        //   Going over all leftover meshes and then doing the following:
         self.entities.unused_rects.iter().for_each(|entity| {
             //   Destructuring queries entity
             let (_transform, mut vis, _, _) =
@@ -385,13 +602,18 @@ impl DrawableOn for Frame<'_, '_, '_, '_, '_, '_, '_, '_, '_, '_> {
 }
 
 fn update_time(mut game_with_menu: ResMut<GameWithMenuResource>) {
-    game_with_menu.0.update(Instant::now());
+    game_with_menu.0.tick();
+    for warning in game_with_menu.0.take_warnings() {
+        eprintln!("{warning}");
+    }
 }
+#[allow(clippy::too_many_arguments)]
 fn draw_frame(
     commands: Commands,
     meshes: ResMut<Assets<Mesh>>,
     materials: ResMut<Assets<ColorMaterial>>,
     mut entities: ResMut<Entities>,
+    asset_server: Res<AssetServer>,
     game_with_menu: ResMut<GameWithMenuResource>,
     rect_query: Query<
         (
@@ -403,9 +625,16 @@ fn draw_frame(
         Without<Text2d>,
     >,
     text_query: Query<
-        (&mut Transform, &mut Visibility, &mut TextColor, &mut Text2d),
+        (
+            &mut Transform,
+            &mut Visibility,
+            &mut TextColor,
+            &mut Text2d,
+            &mut TextFont,
+        ),
         Without<MeshMaterial2d<ColorMaterial>>,
     >,
+    sprite_query: Query<(&mut Transform, &mut Visibility, &mut Sprite), SpriteFilter>,
 ) {
     //   Adding all used rectangles to the unused ones.
     {
@@ -418,15 +647,29 @@ fn draw_frame(
         buffer.append(&mut entities.used_text);
         entities.unused_text.append(&mut buffer);
     }
+    {
+        let mut buffer = Vec::new();
+        buffer.append(&mut entities.used_sprites);
+        entities.unused_sprites.append(&mut buffer);
+    }
     let mut frame = Frame {
         commands,
         meshes,
         materials,
         entities,
+        asset_server,
         rect_query,
         text_query,
+        sprite_query,
+        depth: 0.,
     };
     game_with_menu.0.draw(&mut frame);
+
+    //   Hiding the sprites that were not drawn this frame
+    for entity in &frame.entities.unused_sprites {
+        let (_transform, mut vis, _) = frame.sprite_query.get_mut(*entity).expect("Cannot fail");
+        *vis = Visibility::Hidden;
+    }
 }
 
 fn touch_system(
@@ -0,0 +1,55 @@
+use std::collections::VecDeque;
+
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use snake_game::{Direction, snakelogic::SnakeLogic};
+
+const SIZE: usize = 100;
+
+/// A snake of `length` squares that winds through the board row by row from the top,
+/// moving towards the next free square.
+fn long_snake(length: usize) -> SnakeLogic {
+    let mut logic = SnakeLogic::with_seed(SIZE, SIZE, 0).expect("The board size is valid");
+    let square = |i: usize| {
+        let y = i / SIZE;
+        let x = if y.is_multiple_of(2) {
+            i % SIZE
+        } else {
+            SIZE - 1 - i % SIZE
+        };
+        (x, y)
+    };
+    let snake: VecDeque<(usize, usize)> = (0..length).map(square).collect();
+    let (head_x, head_y) = square(length - 1);
+    logic.set_snake(snake);
+    logic.change_direction(if head_y.is_multiple_of(2) && head_x < SIZE - 1 {
+        Direction::Right
+    } else if !head_y.is_multiple_of(2) && head_x > 0 {
+        Direction::Left
+    } else {
+        Direction::Down
+    });
+    logic
+}
+
+fn step(c: &mut Criterion) {
+    let mut group = c.benchmark_group("next_step");
+    for length in [10, 100, 1_000, 5_000, 9_000] {
+        let logic = long_snake(length);
+        group.bench_with_input(BenchmarkId::from_parameter(length), &logic, |b, logic| {
+            b.iter_batched_ref(
+                || {
+                    // The first step after cloning grows the snake's buffer, so measure the one after it.
+                    let mut logic = logic.clone();
+                    let _ = logic.next_step();
+                    logic
+                },
+                |logic| logic.next_step(),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, step);
+criterion_main!(benches);
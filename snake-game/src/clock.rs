@@ -0,0 +1,81 @@
+//! Where the game gets the current time from.
+//!
+//! [`GameWithMenu`](crate::game_with_menu::GameWithMenu) reads its clock on every
+//! [`tick`](crate::game_with_menu::GameWithMenu::tick), and the frontends give it a [`SystemClock`].
+//! Tests and bots use [`ManualClock`] and move the time forward themselves,
+//! so they never have to sleep. To run a game as fast as possible, call [`SnakeGame::step`](crate::game::SnakeGame::step) instead.
+
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+use web_time::{Duration, Instant};
+
+/// Something that tells the current time.
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+impl Default for Box<dyn Clock> {
+    fn default() -> Self {
+        Box::new(SystemClock)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+/// The real time.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A clock that only moves when [`ManualClock::advance`] is called.
+/// Clones share their time, so a clone given to a game moves with the original.
+pub struct ManualClock {
+    now: Arc<Mutex<Instant>>,
+}
+
+impl ManualClock {
+    /// Creates a clock that stands at the moment it was created.
+    pub fn new() -> Self {
+        ManualClock {
+            now: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Moves the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().expect("Poisoned") += duration;
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().expect("Poisoned")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manual_clock() {
+        let clock = ManualClock::new();
+        let shared = clock.clone();
+        let start = clock.now();
+        assert_eq!(clock.now(), start);
+        clock.advance(Duration::from_millis(250));
+        assert_eq!(clock.now() - start, Duration::from_millis(250));
+        assert_eq!(shared.now(), clock.now());
+    }
+}
@@ -0,0 +1,106 @@
+//! The kinds of food, what they are worth and how long their effects last.
+//!
+//! What eating each kind does to the snake and the game is in
+//! [`SnakeGame`](crate::game::SnakeGame).
+
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+/// How many ticks bonus food stays on the board before it disappears.
+pub const BONUS_FOOD_TICKS: u32 = 40;
+/// How many ticks the effect of speed-up, slow-down and golden food lasts.
+pub const EFFECT_TICKS: u64 = 60;
+/// How many tail squares shrink food removes.
+pub const SHRINK_AMOUNT: usize = 3;
+/// What the points of food are multiplied by while the effect of golden food lasts.
+pub const GOLDEN_MULTIPLIER: usize = 2;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, strum::Display)]
+/// This enum tells what kind of food it is and what happens when the snake eats it.
+pub enum FoodKind {
+    /// Makes the snake grow.
+    #[default]
+    Normal,
+    /// Worth more points, but it disappears after [`BONUS_FOOD_TICKS`] ticks.
+    Bonus,
+    /// Removes [`SHRINK_AMOUNT`] squares from the tail instead of growing.
+    Shrink,
+    /// Makes the game faster for [`EFFECT_TICKS`] ticks.
+    SpeedUp,
+    /// Makes the game slower for [`EFFECT_TICKS`] ticks.
+    SlowDown,
+    /// Multiplies the points of all food by [`GOLDEN_MULTIPLIER`] for [`EFFECT_TICKS`] ticks.
+    Golden,
+}
+
+impl FoodKind {
+    /// The points this food is worth before any multiplier.
+    pub fn points(&self) -> usize {
+        match self {
+            FoodKind::Normal | FoodKind::Shrink | FoodKind::SlowDown => 1,
+            FoodKind::SpeedUp | FoodKind::Golden => 2,
+            FoodKind::Bonus => 5,
+        }
+    }
+
+    /// Picks a kind for new food. Most food is [`FoodKind::Normal`].
+    pub(crate) fn random(rng: &mut ChaCha8Rng) -> FoodKind {
+        match rng.random_range(0..100) {
+            0..70 => FoodKind::Normal,
+            70..80 => FoodKind::Bonus,
+            80..86 => FoodKind::Shrink,
+            86..91 => FoodKind::SpeedUp,
+            91..96 => FoodKind::SlowDown,
+            _ => FoodKind::Golden,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// A food item on the board.
+pub struct Food {
+    position: (usize, usize),
+    kind: FoodKind,
+    /// How many ticks are left until the food disappears, if it does.
+    ticks_left: Option<u32>,
+}
+
+impl From<(usize, usize)> for Food {
+    /// Normal food on the given square.
+    fn from(position: (usize, usize)) -> Self {
+        Food::new(position, FoodKind::Normal)
+    }
+}
+
+impl Food {
+    pub fn new(position: (usize, usize), kind: FoodKind) -> Self {
+        Food {
+            position,
+            kind,
+            ticks_left: (kind == FoodKind::Bonus).then_some(BONUS_FOOD_TICKS),
+        }
+    }
+
+    pub fn position(&self) -> (usize, usize) {
+        self.position
+    }
+
+    pub fn kind(&self) -> FoodKind {
+        self.kind
+    }
+
+    pub fn ticks_left(&self) -> Option<u32> {
+        self.ticks_left
+    }
+
+    /// Counts down one tick. Gives [`true`] if the food has disappeared now.
+    pub(crate) fn tick(&mut self) -> bool {
+        match &mut self.ticks_left {
+            Some(ticks_left) => {
+                *ticks_left = ticks_left.saturating_sub(1);
+                *ticks_left == 0
+            }
+            None => false,
+        }
+    }
+}
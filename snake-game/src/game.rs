@@ -1,106 +1,254 @@
-use crate::Direction;
-use crate::internal::GameResult;
+use crate::food::{EFFECT_TICKS, Food, FoodKind, GOLDEN_MULTIPLIER};
+use crate::internal::{GameEvent, GameResult};
+use crate::preset::Preset;
+use crate::replay::{Playback, Replay, ReplayStatus};
+use crate::score::Score;
 use crate::snakelogic::SnakeLogic;
-use std::collections::VecDeque;
+use crate::{Direction, WallMode};
+use std::collections::{BTreeSet, VecDeque};
 
 use web_time::{Duration, Instant};
 
+/// The most logic steps one [`SnakeGame::update`] does, however long ago the last one was.
+pub const MAX_STEPS_PER_UPDATE: u32 = 5;
+/// No logic step is shorter than this, so [`SnakeGame::update`] always gets through its time.
+pub const MIN_TIMESTEP: Duration = Duration::from_millis(1);
+
 #[derive(Debug)]
 pub struct SnakeGame {
     snake_logic: SnakeLogic,
-    now: Instant,
     paused: bool,
-    last_logic_update: Instant,
+    /// When [`SnakeGame::update`] was called last, or [`None`] before the first call.
+    last_update: Option<Instant>,
+    /// Time that has passed but is not used up by logic steps yet.
+    accumulator: Duration,
     last_game_result: GameResult,
     timestep: Duration,
+    difficulty: Preset,
+    /// The number of logic steps done so far.
+    tick: u64,
+    /// The timesteps of all logic steps done so far added up.
+    duration: Duration,
+    /// Every direction change with the tick it was made in, so the game can be saved as a [`Replay`].
+    inputs: Vec<(u64, Direction)>,
+    /// Set when the game is a replay. The player cannot steer then.
+    playback: Option<Playback>,
+    /// What happened during the last call to [`SnakeGame::update`] or [`SnakeGame::step`].
+    events: Vec<GameEvent>,
+    score: Score,
+    /// The speed-up or slow-down food whose effect lasts, with the number of ticks it still lasts.
+    speed_effect: Option<(FoodKind, u64)>,
+    /// The number of ticks the multiplier of golden food still lasts.
+    golden_ticks_left: u64,
+    /// Set when the game gets faster with every food eaten.
+    speed_ramp: Option<SpeedRamp>,
+    foods_eaten: u64,
+    /// The tail and the head before the last logic step, if the snake moved in it.
+    previous_ends: Option<((usize, usize), (usize, usize))>,
 }
 
 impl Default for SnakeGame {
     fn default() -> Self {
         Self {
             snake_logic: SnakeLogic::new(25, 25).expect("Cannot fail"),
-            now: Instant::now(),
             paused: Default::default(),
-            last_logic_update: Instant::now(),
+            last_update: None,
+            accumulator: Duration::ZERO,
             last_game_result: Default::default(),
-            timestep: Default::default(),
+            timestep: Preset::default().timestep(),
+            difficulty: Preset::default(),
+            tick: 0,
+            duration: Duration::ZERO,
+            inputs: Vec::new(),
+            playback: None,
+            events: Vec::new(),
+            score: Score::default(),
+            speed_effect: None,
+            golden_ticks_left: 0,
+            speed_ramp: None,
+            foods_eaten: 0,
+            previous_ends: None,
         }
     }
 }
 
-#[derive(Debug)]
-pub enum GameDifficulty {
-    Hard,
-    Normal,
-    Easy,
-    Basic,
-    VeryHard,
-    Extreme,
-    Intermediate,
-    Expert,
-    Insane,
-    VeryEasy,
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+/// The rules of a game that can be chosen next to its [`Preset`].
+pub struct GameOptions {
+    pub wall_mode: WallMode,
+    /// How many food items are on the board at once.
+    pub food_count: usize,
+    /// Makes the game faster with every food eaten, following [`GameOptions::ramp`].
+    pub speed_ramp: bool,
+    pub ramp: SpeedRamp,
 }
 
-impl GameDifficulty {
-    pub const TIMESTEP_NORMAL: Duration = Duration::from_millis(100);
-    pub const TIMESTEP_EASY: Duration = Duration::from_millis(200);
-    pub const TIMESTEP_HARD: Duration = Duration::from_millis(75);
-    pub const TIMESTEP_EXTREME: Duration = Duration::from_millis(35);
-    pub const TIMESTEP_INSANE: Duration = Duration::from_millis(20);
-    pub const TIMESTEP_VERYEASY: Duration = Duration::from_millis(400);
-    pub const TIMESTEP_EXPERT: Duration = Duration::from_millis(10);
-    pub const TIMESTEP_INTERMEDIATE: Duration = Duration::from_millis(60);
-    pub const TIMESTEP_BASIC: Duration = Duration::from_millis(150);
-    pub const TIMESTEP_VERYHARD: Duration = Duration::from_millis(30);
+impl GameOptions {
+    pub const MAX_FOOD_COUNT: usize = 10;
 
-    pub fn timestep(&self) -> Duration {
-        match self {
-            GameDifficulty::Hard => Self::TIMESTEP_HARD,
-            GameDifficulty::Normal => Self::TIMESTEP_NORMAL,
-            GameDifficulty::Easy => Self::TIMESTEP_EASY,
-            GameDifficulty::Extreme => Self::TIMESTEP_EXTREME,
-            GameDifficulty::Insane => Self::TIMESTEP_INSANE,
-            GameDifficulty::VeryEasy => Self::TIMESTEP_VERYEASY,
-            GameDifficulty::Intermediate => Self::TIMESTEP_INTERMEDIATE,
-            GameDifficulty::Expert => Self::TIMESTEP_EXPERT,
-            GameDifficulty::Basic => Self::TIMESTEP_BASIC,
-            GameDifficulty::VeryHard => Self::TIMESTEP_VERYHARD,
-        }
-    }
-
-    pub fn game_size(&self) -> (usize, usize) {
-        match self {
-            GameDifficulty::Hard => (35, 35),
-            GameDifficulty::Normal => (25, 25),
-            GameDifficulty::Easy => (15, 15),
-            GameDifficulty::Extreme => (70, 70),
-            GameDifficulty::Insane => (85, 85),
-            GameDifficulty::VeryEasy => (8, 8),
-            GameDifficulty::Intermediate => (45, 45),
-            GameDifficulty::Expert => (100, 100),
-            GameDifficulty::Basic => (11, 11),
-            GameDifficulty::VeryHard => (55, 55),
+    pub fn next_food_count(&mut self) {
+        self.food_count = self.food_count % Self::MAX_FOOD_COUNT + 1;
+    }
+
+    pub fn previous_food_count(&mut self) {
+        self.food_count = (self.food_count + Self::MAX_FOOD_COUNT - 2) % Self::MAX_FOOD_COUNT + 1;
+    }
+}
+
+impl Default for GameOptions {
+    fn default() -> Self {
+        GameOptions {
+            wall_mode: WallMode::default(),
+            food_count: 1,
+            speed_ramp: false,
+            ramp: SpeedRamp::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+/// How the tick duration gets shorter while the snake eats.
+pub struct SpeedRamp {
+    /// The tick duration the game starts with, in milliseconds. [`None`] starts with the one of the preset.
+    pub start_millis: Option<u64>,
+    /// The tick duration never gets shorter than this, in milliseconds, unless it starts shorter.
+    /// It never gets shorter than [`Preset::MIN_TICK_MILLIS`] either way.
+    pub floor_millis: u64,
+    /// How many milliseconds every food eaten takes off the tick duration.
+    pub step_millis: u64,
+}
+
+impl SpeedRamp {
+    /// Gives [`true`] if the start and the floor are tick durations a preset can have.
+    pub fn is_valid(&self) -> bool {
+        *self == self.clamped()
+    }
+
+    /// Brings the start and the floor within [`Preset::MIN_TICK_MILLIS`] and [`Preset::MAX_TICK_MILLIS`].
+    pub fn clamped(self) -> SpeedRamp {
+        let clamp = |millis: u64| millis.clamp(Preset::MIN_TICK_MILLIS, Preset::MAX_TICK_MILLIS);
+        SpeedRamp {
+            start_millis: self.start_millis.map(clamp),
+            floor_millis: clamp(self.floor_millis),
+            ..self
+        }
+    }
+
+    /// The tick duration after `foods_eaten` food in a game whose preset has `timestep`.
+    pub fn timestep(&self, timestep: Duration, foods_eaten: u64) -> Duration {
+        let start = self.start_millis.map_or(timestep, Duration::from_millis);
+        let floor = Duration::from_millis(self.floor_millis)
+            .min(start)
+            .max(Duration::from_millis(Preset::MIN_TICK_MILLIS));
+        start
+            .saturating_sub(Duration::from_millis(
+                self.step_millis.saturating_mul(foods_eaten),
+            ))
+            .max(floor)
+    }
+}
+
+impl Default for SpeedRamp {
+    fn default() -> Self {
+        SpeedRamp {
+            start_millis: None,
+            floor_millis: 40,
+            step_millis: 2,
         }
     }
 }
 
 impl SnakeGame {
-    pub fn new(difficulty: GameDifficulty) -> SnakeGame {
+    pub fn new(difficulty: Preset, options: GameOptions) -> SnakeGame {
+        Self::with_seed(difficulty, options, rand::random())
+    }
+
+    /// Creates a game whose snake and food positions are fully decided by `seed`.
+    pub fn with_seed(difficulty: Preset, options: GameOptions, seed: u64) -> SnakeGame {
         let (width, height) = difficulty.game_size();
-        let snake_logic = SnakeLogic::new(width, height).expect("Cannot fail");
-        let now = Instant::now();
+        let mut snake_logic = SnakeLogic::with_seed(width, height, seed).expect("Cannot fail");
+        snake_logic.set_wall_mode(options.wall_mode);
+        snake_logic.set_food_count(options.food_count);
+        snake_logic.set_growth_per_food(difficulty.growth);
 
         SnakeGame {
             snake_logic,
-            now,
             paused: false,
-            last_logic_update: Instant::now(),
+            last_update: None,
+            accumulator: Duration::ZERO,
             last_game_result: GameResult::NoOp,
             timestep: difficulty.timestep(),
+            difficulty,
+            tick: 0,
+            duration: Duration::ZERO,
+            inputs: Vec::new(),
+            playback: None,
+            events: Vec::new(),
+            score: Score::default(),
+            speed_effect: None,
+            golden_ticks_left: 0,
+            speed_ramp: options.speed_ramp.then_some(options.ramp),
+            foods_eaten: 0,
+            previous_ends: None,
+        }
+    }
+
+    /// Creates a game that plays `replay` back. Direction changes only come from the replay.
+    pub fn from_replay(replay: &Replay) -> SnakeGame {
+        let mut game =
+            Self::with_seed(replay.difficulty().clone(), replay.options(), replay.seed());
+        game.playback = Some(Playback::new(replay));
+        game
+    }
+
+    /// Gives everything needed to play this game again.
+    pub fn replay(&self) -> Replay {
+        Replay {
+            seed: self.seed(),
+            difficulty: self.difficulty.clone(),
+            options: self.options(),
+            inputs: self.inputs.clone(),
+            ticks: self.tick,
+            score: self.score(),
         }
     }
 
+    /// Tells how a replay that is being played back is going, or [`None`] if this game is not a replay.
+    pub fn replay_status(&self) -> Option<ReplayStatus> {
+        self.playback
+            .as_ref()
+            .map(|playback| playback.status(self.is_over(), self.tick, self.score()))
+    }
+
+    pub fn options(&self) -> GameOptions {
+        GameOptions {
+            wall_mode: self.snake_logic.wall_mode(),
+            food_count: self.snake_logic.food_count(),
+            speed_ramp: self.speed_ramp.is_some(),
+            ramp: self.speed_ramp.unwrap_or_default(),
+        }
+    }
+
+    pub fn difficulty(&self) -> &Preset {
+        &self.difficulty
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// How long the game has been played, not counting pauses.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// The seed this game was created with. Starting a game with the same seed reproduces it.
+    pub fn seed(&self) -> u64 {
+        self.snake_logic.seed()
+    }
+
     pub fn height(&self) -> usize {
         self.snake_logic.height()
     }
@@ -109,20 +257,70 @@ impl SnakeGame {
         self.snake_logic.width()
     }
 
-    pub fn food(&self) -> (usize, usize) {
-        self.snake_logic.food()
+    /// All the food on the board.
+    pub fn food(&self) -> impl Iterator<Item = Food> + '_ {
+        self.snake_logic.food().iter().copied()
     }
 
     pub fn snake(&self) -> &VecDeque<(usize, usize)> {
         self.snake_logic.snake()
     }
 
-    #[cfg(test)]
+    pub fn obstacles(&self) -> &BTreeSet<(usize, usize)> {
+        self.snake_logic.obstacles()
+    }
+
+    /// The squares to draw for the snake `alpha` of the way from the last logic step to the next one,
+    /// in board coordinates from the tail to the head. The head glides into its square and the tail glides
+    /// out of the square it left, so the snake moves smoothly instead of jumping from square to square.
+    /// Nothing glides across the board when the snake wraps around an edge.
+    pub fn interpolated_snake(&self, alpha: f32) -> Vec<(f32, f32)> {
+        let snake = self.snake();
+        let square = |(x, y): (usize, usize)| (x as f32, y as f32);
+        let Some((tail_before, head_before)) = self.previous_ends else {
+            return snake.iter().copied().map(square).collect();
+        };
+        let glide = |from: (usize, usize), to: (usize, usize)| {
+            if from.0.abs_diff(to.0) + from.1.abs_diff(to.1) == 1 {
+                let (from, to) = (square(from), square(to));
+                (
+                    from.0 + (to.0 - from.0) * alpha,
+                    from.1 + (to.1 - from.1) * alpha,
+                )
+            } else {
+                square(to)
+            }
+        };
+
+        let (tail, head) = self.snake_ends();
+        let mut squares = Vec::with_capacity(snake.len() + 1);
+        if tail_before != tail && tail_before != head {
+            squares.push(glide(tail_before, tail));
+        }
+        squares.extend(snake.iter().take(snake.len() - 1).copied().map(square));
+        squares.push(glide(head_before, head));
+        squares
+    }
+
+    /// The tail and the head of the snake.
+    fn snake_ends(&self) -> ((usize, usize), (usize, usize)) {
+        let snake = self.snake();
+        match (snake.front(), snake.back()) {
+            (Some(tail), Some(head)) => (*tail, *head),
+            _ => unreachable!("The snake is never empty"),
+        }
+    }
+
+    /// The direction the snake goes in the next step.
     pub fn direction(&self) -> Direction {
         self.snake_logic.direction()
     }
 
     pub fn change_direction(&mut self, direction: Direction) {
+        if self.playback.is_some() || self.is_over() {
+            return;
+        }
+        self.inputs.push((self.tick, direction));
         self.snake_logic.change_direction(direction)
     }
 
@@ -134,23 +332,371 @@ impl SnakeGame {
         self.paused
     }
 
+    /// All the points so far. See [`SnakeGame::score_breakdown`] for where they came from.
     pub fn score(&self) -> usize {
-        self.snake().len()
+        self.score.total()
+    }
+
+    pub fn score_breakdown(&self) -> &Score {
+        &self.score
+    }
+
+    /// The time between two logic steps. The speed ramp shortens it with every food eaten,
+    /// and speed-up and slow-down food change it for a while.
+    pub fn timestep(&self) -> Duration {
+        let timestep = match self.speed_ramp {
+            Some(ramp) => ramp.timestep(self.timestep, self.foods_eaten),
+            None => self.timestep,
+        };
+        let timestep = match self.speed_effect {
+            Some((FoodKind::SpeedUp, _)) => timestep * 2 / 3,
+            Some((FoodKind::SlowDown, _)) => timestep * 3 / 2,
+            _ => timestep,
+        };
+        timestep.max(MIN_TIMESTEP)
+    }
+
+    /// What the points of food are multiplied by right now.
+    pub fn multiplier(&self) -> usize {
+        if self.golden_ticks_left > 0 {
+            GOLDEN_MULTIPLIER
+        } else {
+            1
+        }
     }
 
     pub fn is_over(&self) -> bool {
-        self.last_game_result == GameResult::GameOver
+        self.last_game_result.is_over()
+    }
+
+    /// Gives [`true`] if the game is over because the snake filled the whole board.
+    pub fn is_victory(&self) -> bool {
+        self.last_game_result.is_victory()
+    }
+    /// What happened in the logic steps of the last [`SnakeGame::update`] or [`SnakeGame::step`], oldest first.
+    pub fn events(&self) -> &[GameEvent] {
+        &self.events
     }
-    pub fn update(&mut self, now: web_time::Instant) {
+
+    /// Does as many logic steps as fit into the time since the last call, but at most
+    /// [`MAX_STEPS_PER_UPDATE`]. Time left over is kept for the next call, see [`SnakeGame::alpha`].
+    /// The first call only starts the timer, so `now` can come from any [`Clock`](crate::clock::Clock).
+    pub fn update(&mut self, now: Instant) {
+        self.events.clear();
         if self.last_game_result.is_over() {
             return;
         }
-        self.now = now;
-        if now - self.last_logic_update > self.timestep {
-            if !self.is_paused() {
-                self.last_game_result = self.snake_logic.next_step();
+        let Some(last_update) = self.last_update.replace(now) else {
+            return;
+        };
+        if self.is_paused() {
+            return;
+        }
+        // Dropping the time beyond the cap keeps a slow machine from falling further and further behind.
+        self.accumulator = (self.accumulator + now.saturating_duration_since(last_update))
+            .min(self.timestep() * MAX_STEPS_PER_UPDATE);
+        while self.accumulator >= self.timestep() && !self.last_game_result.is_over() {
+            self.accumulator -= self.timestep();
+            self.logic_step();
+        }
+    }
+
+    /// How far the game is between the last logic step and the next one, from 0 to 1.
+    /// Drawing the snake that far along its way makes it move smoothly.
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator.as_secs_f32() / self.timestep().as_secs_f32()).clamp(0.0, 1.0)
+    }
+
+    /// Does exactly one logic step right away, without looking at the time or at [`SnakeGame::is_paused`].
+    /// This is for running games without a window, for example in tests or for bots.
+    /// It does nothing once the game is over.
+    pub fn step(&mut self) {
+        self.events.clear();
+        if self.last_game_result.is_over() {
+            return;
+        }
+        self.logic_step();
+    }
+
+    /// Does exactly one logic step, feeding in the replay's direction changes for this tick first.
+    fn logic_step(&mut self) {
+        if let Some(playback) = &mut self.playback {
+            while let Some(direction) = playback.next_input(self.tick) {
+                self.snake_logic.change_direction(direction);
             }
-            self.last_logic_update = now;
         }
+        self.duration += self.timestep();
+        let ends = self.snake_ends();
+        self.previous_ends = None;
+        self.last_game_result = self.snake_logic.next_step();
+        let events = self.snake_logic.take_events();
+        self.events.extend(&events);
+        self.count_down_effects();
+        for event in events {
+            match event {
+                GameEvent::Moved { .. } => {
+                    self.previous_ends = Some(ends);
+                    self.score.moved(self.difficulty.points_multiplier)
+                }
+                GameEvent::FoodEaten { kind, .. } => self.eat(kind),
+                _ => (),
+            }
+        }
+        self.tick += 1;
+    }
+
+    /// Adds the points of the food and starts its effect, if it has one.
+    fn eat(&mut self, kind: FoodKind) {
+        self.foods_eaten += 1;
+        self.score.food_eaten(
+            kind.points() * self.multiplier(),
+            self.difficulty.points_multiplier,
+            self.tick,
+        );
+        match kind {
+            FoodKind::SpeedUp | FoodKind::SlowDown => {
+                self.speed_effect = Some((kind, EFFECT_TICKS))
+            }
+            FoodKind::Golden => self.golden_ticks_left = EFFECT_TICKS,
+            FoodKind::Normal | FoodKind::Bonus | FoodKind::Shrink => (),
+        }
+    }
+
+    fn count_down_effects(&mut self) {
+        if let Some((kind, ticks_left)) = &mut self.speed_effect {
+            *ticks_left -= 1;
+            if *ticks_left == 0 {
+                self.events.push(GameEvent::EffectEnded { kind: *kind });
+                self.speed_effect = None;
+            }
+        }
+        if self.golden_ticks_left > 0 {
+            self.golden_ticks_left -= 1;
+            if self.golden_ticks_left == 0 {
+                self.events.push(GameEvent::EffectEnded {
+                    kind: FoodKind::Golden,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, ManualClock};
+    use crate::preset::Presets;
+
+    fn preset(name: &str) -> Preset {
+        Presets::builtin().get(name).unwrap().clone()
+    }
+
+    #[test]
+    fn update_with_manual_clock() {
+        let clock = ManualClock::new();
+        let mut game = SnakeGame::with_seed(preset("Normal"), GameOptions::default(), 3);
+        let timestep = preset("Normal").timestep();
+
+        // The first update only starts the timer.
+        game.update(clock.now());
+        assert_eq!(game.tick(), 0);
+
+        clock.advance(timestep / 2);
+        game.update(clock.now());
+        assert_eq!(game.tick(), 0);
+        assert_eq!(game.alpha(), 0.5);
+
+        clock.advance(timestep / 2);
+        game.update(clock.now());
+        assert_eq!(game.tick(), 1);
+        assert_eq!(game.alpha(), 0.0);
+
+        // A slow frame catches up on all the steps it missed.
+        clock.advance(timestep * 3 + timestep / 4);
+        game.update(clock.now());
+        assert_eq!(game.tick(), 4);
+        assert_eq!(game.alpha(), 0.25);
+
+        game.set_paused(true);
+        clock.advance(timestep * 2);
+        game.update(clock.now());
+        assert_eq!(game.tick(), 4);
+        assert_eq!(game.alpha(), 0.25);
+        game.set_paused(false);
+
+        clock.advance(timestep * 100);
+        game.update(clock.now());
+        assert_eq!(game.tick(), 4 + MAX_STEPS_PER_UPDATE as u64);
+    }
+
+    #[test]
+    fn update_with_zero_timestep() {
+        let clock = ManualClock::new();
+        let options = GameOptions {
+            speed_ramp: true,
+            ramp: SpeedRamp {
+                start_millis: Some(0),
+                floor_millis: 0,
+                step_millis: 2,
+            },
+            ..GameOptions::default()
+        };
+        let mut game = SnakeGame::with_seed(preset("Normal"), options, 3);
+        assert!(game.timestep() >= MIN_TIMESTEP);
+        game.update(clock.now());
+        clock.advance(Duration::from_secs(1));
+        game.update(clock.now());
+        assert!(game.tick() <= MAX_STEPS_PER_UPDATE as u64);
+
+        let zero = Preset {
+            tick_millis: 0,
+            ..preset("Normal")
+        };
+        let mut game = SnakeGame::with_seed(zero, GameOptions::default(), 3);
+        assert_eq!(game.timestep(), MIN_TIMESTEP);
+        game.update(clock.now());
+        clock.advance(Duration::from_secs(1));
+        game.update(clock.now());
+        assert_eq!(game.tick(), MAX_STEPS_PER_UPDATE as u64);
+
+        let game = SnakeGame::default();
+        assert_eq!(game.timestep(), Preset::default().timestep());
+        assert_eq!(game.alpha(), 0.0);
+    }
+
+    #[test]
+    fn step() {
+        for seed in 0..1000 {
+            let mut game = SnakeGame::with_seed(preset("Easy"), GameOptions::default(), seed);
+            let (start_x, _) = game.snake()[0];
+            game.change_direction(Direction::Right);
+            while !game.is_over() {
+                game.step();
+            }
+            assert!(matches!(game.events().last(), Some(GameEvent::Died { .. })));
+            assert_eq!(game.tick(), (15 - start_x) as u64);
+
+            game.step();
+            assert!(game.events().is_empty());
+        }
+    }
+
+    #[test]
+    fn food_effects() {
+        let mut game = SnakeGame::with_seed(preset("Normal"), GameOptions::default(), 1);
+        let timestep = preset("Normal").timestep();
+        game.snake_logic.set_wall_mode(WallMode::WrapAround);
+        game.snake_logic.set_snake(vec![(0, 0)].into());
+        game.snake_logic
+            .set_food(vec![Food::new((1, 0), FoodKind::Golden)]);
+        game.change_direction(Direction::Right);
+
+        game.step();
+        assert_eq!(game.score_breakdown().food(), 2 * 2);
+        assert_eq!(game.multiplier(), GOLDEN_MULTIPLIER);
+
+        game.snake_logic
+            .set_food(vec![Food::new((2, 0), FoodKind::SpeedUp)]);
+        game.step();
+        assert_eq!(
+            game.score_breakdown().food(),
+            (2 + 2 * GOLDEN_MULTIPLIER) * 2
+        );
+        assert_eq!(game.score_breakdown().streak_bonus(), 2);
+        assert!(game.timestep() < timestep);
+
+        game.snake_logic.set_food(vec![(0, 24)]);
+        while !game.events().contains(&GameEvent::EffectEnded {
+            kind: FoodKind::Golden,
+        }) {
+            game.step();
+        }
+        assert_eq!(game.multiplier(), 1);
+        assert!(game.timestep() < timestep);
+        game.step();
+        assert!(game.events().contains(&GameEvent::EffectEnded {
+            kind: FoodKind::SpeedUp
+        }));
+        assert_eq!(game.timestep(), timestep);
+        assert!(!game.is_over());
+    }
+
+    #[test]
+    fn custom_difficulty() {
+        let custom = Preset {
+            width: 30,
+            height: 20,
+            tick_millis: 40,
+            growth: 1,
+            ..Preset::default()
+        };
+        let game = SnakeGame::new(custom.clone(), GameOptions::default());
+        assert_eq!((game.width(), game.height()), (30, 20));
+        assert_eq!(game.snake_logic.growth_per_food(), 1);
+        assert_eq!(game.timestep(), Duration::from_millis(40));
+        assert_eq!(game.replay().difficulty(), &custom);
+    }
+
+    #[test]
+    fn interpolated_snake() {
+        let mut game = SnakeGame::with_seed(preset("Normal"), GameOptions::default(), 1);
+        game.snake_logic
+            .set_snake(vec![(3, 5), (4, 5), (5, 5)].into());
+        game.snake_logic.set_food(vec![(0, 0)]);
+        assert_eq!(
+            game.interpolated_snake(0.5),
+            [(3.0, 5.0), (4.0, 5.0), (5.0, 5.0)]
+        );
+
+        game.change_direction(Direction::Down);
+        game.step();
+        assert_eq!(
+            game.interpolated_snake(0.25),
+            [(3.25, 5.0), (4.0, 5.0), (5.0, 5.0), (5.0, 5.25)]
+        );
+        assert_eq!(game.interpolated_snake(1.0)[3], (5.0, 6.0));
+
+        game.snake_logic.set_wall_mode(WallMode::WrapAround);
+        game.snake_logic.set_snake(vec![(1, 0), (0, 0)].into());
+        game.change_direction(Direction::Left);
+        game.step();
+        assert_eq!(
+            game.interpolated_snake(0.5),
+            [(0.5, 0.0), (0.0, 0.0), (24.0, 0.0)]
+        );
+    }
+
+    #[test]
+    fn speed_ramp() {
+        let options = GameOptions {
+            wall_mode: WallMode::WrapAround,
+            speed_ramp: true,
+            ramp: SpeedRamp {
+                start_millis: Some(60),
+                floor_millis: 45,
+                step_millis: 10,
+            },
+            ..GameOptions::default()
+        };
+        let mut game = SnakeGame::with_seed(preset("Normal"), options, 1);
+        assert_eq!(game.timestep(), Duration::from_millis(60));
+        assert_eq!(game.options(), options);
+        game.snake_logic.set_snake(vec![(0, 0)].into());
+        game.change_direction(Direction::Right);
+        for x in 1..4 {
+            game.snake_logic.set_food(vec![(x, 0)]);
+            game.step();
+        }
+        assert_eq!(game.timestep(), Duration::from_millis(45));
+
+        let ramp = SpeedRamp::default();
+        let timestep = preset("Expert").timestep();
+        assert_eq!(ramp.timestep(timestep, 0), timestep);
+        assert_eq!(ramp.timestep(timestep, 100), timestep);
+        let timestep = preset("Easy").timestep();
+        assert_eq!(
+            ramp.timestep(timestep, 5),
+            timestep - Duration::from_millis(5 * ramp.step_millis)
+        );
     }
 }
@@ -1,7 +1,16 @@
+use std::path::PathBuf;
+
 use crate::{
-    Direction,
-    game::{GameDifficulty, SnakeGame},
-    menu::{Difficulty, Menu, MenuAction, MenuType, SelectedSetting},
+    Direction, GameEvent,
+    clock::Clock,
+    game::SnakeGame,
+    high_scores::{self, HighScore, HighScores, NameEntry},
+    layout::Layout,
+    menu::{Menu, MenuAction, MenuType, SelectedSetting, Settings},
+    preset::Presets,
+    render,
+    replay::{Replay, ReplayStatus},
+    theme::Themes,
     traits::DrawableOn,
 };
 
@@ -10,29 +19,230 @@ pub struct GameWithMenu {
     game: SnakeGame,
     menu: Menu,
     game_or_menu: GameOrMenu,
+    /// Where to save the replay of every game that ends.
+    record_path: Option<PathBuf>,
+    high_scores: HighScores,
+    /// Where the high scores are saved. They are only kept in memory without it.
+    high_scores_path: Option<PathBuf>,
+    /// Set after a game that gets into the high scores, until the player has typed their name.
+    name_entry: Option<NameEntry>,
+    /// The name typed last time, to start the next name entry with.
+    last_name: String,
+    /// Where the settings are saved whenever they change.
+    settings_path: Option<PathBuf>,
+    /// Read on every [`GameWithMenu::tick`].
+    clock: Box<dyn Clock>,
+    /// Files that could not be loaded or saved, until the frontend takes them with [`GameWithMenu::take_warnings`].
+    warnings: Vec<String>,
 }
 impl GameWithMenu {
+    /// Creates the game from the command line arguments (without the program name), running on `clock`.
+    /// `--replay FILE` starts by playing back a replay and `--record FILE` saves the replay of every finished game.
+    /// Presets, themes, settings and high scores that cannot be loaded are left at their defaults and
+    /// reported by [`GameWithMenu::take_warnings`].
+    pub fn from_args(
+        args: impl IntoIterator<Item = String>,
+        clock: impl Clock + 'static,
+    ) -> Result<Self, String> {
+        let mut game_with_menu = GameWithMenu {
+            clock: Box::new(clock),
+            high_scores_path: HighScores::default_path(),
+            settings_path: Settings::default_path(),
+            ..GameWithMenu::default()
+        };
+        if let Some(path) = Presets::default_path() {
+            match Presets::load(&path) {
+                Ok(presets) => game_with_menu.menu.set_presets(presets),
+                Err(error) => game_with_menu.warn(format!(
+                    "Cannot load the presets from {}: {error}",
+                    path.display()
+                )),
+            }
+        }
+        if let Some(path) = Themes::default_path() {
+            match Themes::load(&path) {
+                Ok(themes) => game_with_menu.menu.set_themes(themes),
+                Err(error) => game_with_menu.warn(format!(
+                    "Cannot load the themes from {}: {error}",
+                    path.display()
+                )),
+            }
+        }
+        if let Some(path) = game_with_menu.settings_path.clone() {
+            match Settings::load(&path) {
+                Ok(settings) => game_with_menu.menu.set_settings(settings),
+                Err(error) => game_with_menu.warn(format!(
+                    "Cannot load the settings from {}: {error}",
+                    path.display()
+                )),
+            }
+        }
+        if let Some(path) = game_with_menu.high_scores_path.clone() {
+            match HighScores::load(&path) {
+                Ok(high_scores) => game_with_menu.high_scores = high_scores,
+                Err(error) => game_with_menu.warn(format!(
+                    "Cannot load the high scores from {}: {error}",
+                    path.display()
+                )),
+            }
+        }
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--replay" => {
+                    let path = args.next().ok_or("--replay needs a file")?;
+                    let replay = Replay::load(&path).map_err(|error| format!("{path}: {error}"))?;
+                    game_with_menu.game = SnakeGame::from_replay(&replay);
+                    game_with_menu.game_or_menu = GameOrMenu::InGame;
+                }
+                "--record" => {
+                    let path = args.next().ok_or("--record needs a file")?;
+                    game_with_menu.record_path = Some(path.into());
+                }
+                _ => {
+                    return Err(format!(
+                        "unknown argument {arg:?}\nusage: snake [--replay FILE] [--record FILE]"
+                    ));
+                }
+            }
+        }
+        Ok(game_with_menu)
+    }
+
     #[cfg(test)]
-    pub fn new(difficulty: GameDifficulty) -> Self {
+    pub fn new(difficulty: crate::preset::Preset) -> Self {
         GameWithMenu {
-            game: SnakeGame::new(difficulty),
+            game: SnakeGame::new(difficulty, Default::default()),
             menu: Menu::new(),
             game_or_menu: GameOrMenu::InMainMenu,
+            record_path: None,
+            high_scores: HighScores::default(),
+            high_scores_path: None,
+            name_entry: None,
+            last_name: String::new(),
+            settings_path: None,
+            clock: Box::default(),
+            warnings: Vec::new(),
         }
     }
 
+    /// Replaces the clock that [`GameWithMenu::tick`] reads.
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.clock = Box::new(clock);
+    }
+
+    /// Moves the game or the menu on to the current time of the clock.
+    pub fn tick(&mut self) {
+        self.update(self.clock.now());
+    }
+
     pub fn update(&mut self, now: web_time::Instant) {
         match self.game_or_menu {
-            GameOrMenu::InGame => {
-                self.game.update(now);
-            }
+            GameOrMenu::InGame => self.advance_game(|game| game.update(now)),
             GameOrMenu::InMainMenu => {
                 self.menu.update(now);
             }
         }
     }
 
+    /// Does exactly one logic step of the game right away, see [`SnakeGame::step`].
+    /// It does nothing while the menu is open.
+    pub fn step(&mut self) {
+        if self.game_or_menu == GameOrMenu::InGame {
+            self.advance_game(SnakeGame::step);
+        }
+    }
+
+    fn advance_game(&mut self, advance: impl FnOnce(&mut SnakeGame)) {
+        let was_over = self.game.is_over();
+        advance(&mut self.game);
+        if !was_over && self.game.is_over() {
+            self.save_replay();
+            self.start_name_entry();
+        }
+    }
+
+    /// Asks for the player's name if the game that just ended gets into the high scores. Replays never do.
+    fn start_name_entry(&mut self) {
+        let difficulty = &self.game.difficulty().name;
+        if self.game.replay_status().is_some()
+            || !self.high_scores.qualifies(difficulty, self.game.score())
+        {
+            return;
+        }
+        let entry = HighScore {
+            name: String::new(),
+            score: self.game.score(),
+            length: self.game.snake().len(),
+            duration: self.game.duration().as_secs(),
+            date: high_scores::now(),
+        };
+        self.name_entry = Some(NameEntry::new(&self.last_name, entry, difficulty));
+    }
+
+    fn finish_name_entry(&mut self) {
+        let Some(name_entry) = self.name_entry.take() else {
+            return;
+        };
+        let (difficulty, entry) = name_entry.finish();
+        self.last_name = entry.name.clone();
+        self.high_scores.insert(&difficulty, entry);
+        if let Some(path) = &self.high_scores_path
+            && let Err(error) = self.high_scores.save(path)
+        {
+            let warning = format!("Cannot save the high scores to {}: {error}", path.display());
+            self.warn(warning);
+        }
+    }
+
+    /// Everything that went wrong with loading or saving files since the last call, oldest first.
+    /// The library doesn't print anything, so the frontend decides how to tell the player.
+    pub fn take_warnings(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    fn warn(&mut self, warning: String) {
+        self.warnings.push(warning);
+    }
+
+    pub fn high_scores(&self) -> &HighScores {
+        &self.high_scores
+    }
+
+    /// What happened in the game during the last [`GameWithMenu::update`] or [`GameWithMenu::step`], oldest first.
+    /// It is empty while the menu is open.
+    pub fn events(&self) -> &[GameEvent] {
+        match self.game_or_menu {
+            GameOrMenu::InGame => self.game.events(),
+            GameOrMenu::InMainMenu => &[],
+        }
+    }
+
+    fn save_replay(&mut self) {
+        if self.game.replay_status().is_some() {
+            return;
+        }
+        if let Some(path) = &self.record_path
+            && let Err(error) = self.game.replay().save(path)
+        {
+            let warning = format!("Cannot save the replay to {}: {error}", path.display());
+            self.warn(warning);
+        }
+    }
+
+    fn save_settings(&mut self) {
+        if let Some(path) = &self.settings_path
+            && let Err(error) = self.menu.settings().save(path)
+        {
+            let warning = format!("Cannot save the settings to {}: {error}", path.display());
+            self.warn(warning);
+        }
+    }
+
     pub fn up_pressed(&mut self) {
+        if let Some(name_entry) = &mut self.name_entry {
+            return name_entry.next_letter();
+        }
         match self.game_or_menu {
             GameOrMenu::InGame => self.game.change_direction(Direction::Up),
             GameOrMenu::InMainMenu => self.menu.select_previous_option(),
@@ -40,21 +250,38 @@ impl GameWithMenu {
     }
 
     pub fn left_pressed(&mut self) {
+        if let Some(name_entry) = &mut self.name_entry {
+            return name_entry.cursor_left();
+        }
         match self.game_or_menu {
             GameOrMenu::InGame => self.game.change_direction(Direction::Left),
             GameOrMenu::InMainMenu => match self.menu.menu_type() {
                 MenuType::MainMenu => (),
-                MenuType::SettingsMenu => match self.menu.menu_type() {
-                    MenuType::MainMenu => (),
-                    MenuType::SettingsMenu => match self.menu.selected_setting() {
+                MenuType::HighScores => self.menu.previous_high_score_difficulty(),
+                MenuType::SettingsMenu => {
+                    match self.menu.selected_setting() {
                         SelectedSetting::Difficulty => self.menu.previous_difficulty(),
-                        SelectedSetting::Back => (),
-                    },
-                },
+                        SelectedSetting::WallMode => self.menu.toggle_wall_mode(),
+                        SelectedSetting::FoodCount => self.menu.previous_food_count(),
+                        SelectedSetting::SpeedRamp => self.menu.toggle_speed_ramp(),
+                        SelectedSetting::SmoothMovement => self.menu.toggle_smooth_movement(),
+                        SelectedSetting::Theme => self.menu.previous_theme(),
+                        SelectedSetting::BodyGradient => self.menu.toggle_body_gradient(),
+                        setting @ (SelectedSetting::Width
+                        | SelectedSetting::Height
+                        | SelectedSetting::TickDuration
+                        | SelectedSetting::Growth) => self.menu.previous_custom_value(setting),
+                        SelectedSetting::Back => return,
+                    }
+                    self.save_settings();
+                }
             },
         }
     }
     pub fn down_pressed(&mut self) {
+        if let Some(name_entry) = &mut self.name_entry {
+            return name_entry.previous_letter();
+        }
         match self.game_or_menu {
             GameOrMenu::InGame => self.game.change_direction(Direction::Down),
             GameOrMenu::InMainMenu => self.menu.select_next_option(),
@@ -62,57 +289,56 @@ impl GameWithMenu {
     }
 
     pub fn right_pressed(&mut self) {
+        if let Some(name_entry) = &mut self.name_entry {
+            return name_entry.cursor_right();
+        }
         match self.game_or_menu {
             GameOrMenu::InGame => self.game.change_direction(Direction::Right),
             GameOrMenu::InMainMenu => match self.menu.menu_type() {
                 MenuType::MainMenu => (),
-                MenuType::SettingsMenu => match self.menu.selected_setting() {
-                    SelectedSetting::Difficulty => self.menu.next_difficulty(),
-                    SelectedSetting::Back => (),
-                },
+                MenuType::HighScores => self.menu.next_high_score_difficulty(),
+                MenuType::SettingsMenu => {
+                    match self.menu.selected_setting() {
+                        SelectedSetting::Difficulty => self.menu.next_difficulty(),
+                        SelectedSetting::WallMode => self.menu.toggle_wall_mode(),
+                        SelectedSetting::FoodCount => self.menu.next_food_count(),
+                        SelectedSetting::SpeedRamp => self.menu.toggle_speed_ramp(),
+                        SelectedSetting::SmoothMovement => self.menu.toggle_smooth_movement(),
+                        SelectedSetting::Theme => self.menu.next_theme(),
+                        SelectedSetting::BodyGradient => self.menu.toggle_body_gradient(),
+                        setting @ (SelectedSetting::Width
+                        | SelectedSetting::Height
+                        | SelectedSetting::TickDuration
+                        | SelectedSetting::Growth) => self.menu.next_custom_value(setting),
+                        SelectedSetting::Back => return,
+                    }
+                    self.save_settings();
+                }
             },
         }
     }
     pub fn enter_or_space_pressed(&mut self) {
+        if self.name_entry.is_some() {
+            return self.finish_name_entry();
+        }
         match self.game_or_menu {
             GameOrMenu::InGame => {
-                let difficulty = match self.menu.settings().difficulty() {
-                    Difficulty::Easy => GameDifficulty::Easy,
-                    Difficulty::Normal => GameDifficulty::Normal,
-                    Difficulty::Hard => GameDifficulty::Hard,
-                    Difficulty::Extreme => GameDifficulty::Extreme,
-                    Difficulty::Insane => GameDifficulty::Insane,
-                    Difficulty::VeryEasy => GameDifficulty::VeryEasy,
-                    Difficulty::Intermediate => GameDifficulty::Intermediate,
-                    Difficulty::Expert => GameDifficulty::Expert,
-                    Difficulty::Basic => GameDifficulty::Basic,
-                    Difficulty::VeryHard => GameDifficulty::VeryHard,
-                };
-
                 if self.game.is_over() {
                     self.game_or_menu = GameOrMenu::InMainMenu;
-                    self.game = SnakeGame::new(difficulty)
+                    let difficulty = self.menu.game_difficulty();
+                    self.game = SnakeGame::new(difficulty, self.menu.settings().options())
                 } else {
                     self.game.set_paused(!self.game.is_paused())
                 }
             }
             GameOrMenu::InMainMenu => {
+                let settings = self.menu.settings().clone();
                 match self.menu.enter_or_space_pressed() {
+                    MenuAction::NoOp if *self.menu.settings() != settings => self.save_settings(),
                     MenuAction::NoOp => (),
                     MenuAction::NewGame => {
-                        let difficulty = match self.menu.settings().difficulty() {
-                            Difficulty::Easy => GameDifficulty::Easy,
-                            Difficulty::Normal => GameDifficulty::Normal,
-                            Difficulty::Hard => GameDifficulty::Hard,
-                            Difficulty::Extreme => GameDifficulty::Extreme,
-                            Difficulty::Insane => GameDifficulty::Insane,
-                            Difficulty::VeryEasy => GameDifficulty::VeryEasy,
-                            Difficulty::Intermediate => GameDifficulty::Intermediate,
-                            Difficulty::Expert => GameDifficulty::Expert,
-                            Difficulty::Basic => GameDifficulty::Basic,
-                            Difficulty::VeryHard => GameDifficulty::VeryHard,
-                        };
-                        self.game = SnakeGame::new(difficulty);
+                        let difficulty = self.menu.game_difficulty();
+                        self.game = SnakeGame::new(difficulty, self.menu.settings().options());
                         self.game_or_menu = GameOrMenu::InGame;
                     }
                 };
@@ -121,67 +347,250 @@ impl GameWithMenu {
     }
 
     pub fn draw<T: DrawableOn>(&self, frame: &mut T) {
-        let game_width = self.game.width();
-        let game_height = self.game.height();
+        let layout = Layout::of_frame(frame, (self.game.width(), self.game.height()));
 
-        let selected_color = (255, 255, 0);
-        let unselected_color = (255, 255, 255);
+        let theme = self.menu.theme();
+        let selected_color = theme.highlight;
+        let unselected_color = theme.text;
 
-        let text_size: u8 = 50;
-        let text_gap: u8 = text_size + 15;
+        let (center_x, center_y) = layout.at(0.5, 0.5);
+        frame.fill_rectangle((frame.width(), frame.height()), theme.background, (0, 0));
 
         match self.game_or_menu {
             GameOrMenu::InGame => {
-                for (snake_x, snake_y) in self.game.snake() {
-                    draw_snake_square(
-                        frame,
-                        (0, 255, 0),
-                        (*snake_x, *snake_y),
-                        (game_width, game_height),
-                    );
+                if theme.has_grid() {
+                    layout.draw_grid(frame, theme.grid);
                 }
-
-                if self.game.is_paused() {
-                    frame.draw_text(
-                        "Paused",
-                        (255, 255, 255),
-                        frame.width() / 2,
-                        frame.height() / 2,
-                        25.,
-                    );
+                layout.draw_border(frame, theme.walls);
+                for obstacle in self.game.obstacles() {
+                    render::draw_snake_square(frame, theme.walls, *obstacle, &layout);
                 }
-                draw_snake_square(
+
+                // The squares go from the tail to the head.
+                let squares: Vec<(f32, f32)> = if self.menu.settings().smooth_movement() {
+                    self.game.interpolated_snake(self.game.alpha())
+                } else {
+                    self.game
+                        .snake()
+                        .iter()
+                        .map(|&(x, y)| (x as f32, y as f32))
+                        .collect()
+                };
+                render::draw_snake(
                     frame,
-                    (255, 0, 0),
-                    self.game.food(),
-                    (game_width, game_height),
+                    &squares,
+                    self.game.direction(),
+                    theme,
+                    self.menu.settings().body_gradient(),
+                    &layout,
                 );
 
+                for food in self.game.food() {
+                    render::draw_snake_square(
+                        frame,
+                        theme.food(food.kind()),
+                        food.position(),
+                        &layout,
+                    );
+                }
+
+                let (score_x, hud_y) = layout.hud_point(0.5);
                 frame.draw_text(
                     &format!("Your score: {:?}", self.game.score()),
-                    (255, 255, 255),
-                    500,
-                    700,
-                    25f32,
+                    unselected_color,
+                    score_x,
+                    hud_y,
+                    layout.font_size(25.),
+                );
+                frame.draw_text(
+                    &format!(
+                        "Speed: {:.1} moves/s",
+                        1.0 / self.game.timestep().as_secs_f32()
+                    ),
+                    theme.info,
+                    layout.hud_point(0.8).0,
+                    hud_y,
+                    layout.font_size(20.),
                 );
+                let streak = self.game.score_breakdown().streak();
+                if streak > 1 && !self.game.is_over() {
+                    frame.draw_text(
+                        &format!("Streak x{streak}"),
+                        theme.highlight,
+                        layout.hud_point(0.2).0,
+                        hud_y,
+                        layout.font_size(20.),
+                    );
+                }
 
-                if self.game.is_over() {
+                if self.game.is_victory() {
+                    frame.draw_text(
+                        &format!(
+                            "You cleared the board! Press space to start a new game. Your score: {:?}",
+                            self.game.score()
+                        ),
+                        theme.success,
+                        center_x,
+                        center_y,
+                        layout.font_size(25.),
+                    );
+                } else if self.game.is_over() {
                     frame.draw_text(
                         &format!(
                             "Game Over. Press space to start a new game. Your score: {:?}",
                             self.game.score()
                         ),
-                        (255, 0, 0),
-                        frame.width() / 2,
-                        frame.height() / 2,
-                        25f32,
+                        theme.warning,
+                        center_x,
+                        center_y,
+                        layout.font_size(25.),
+                    );
+                }
+
+                if self.game.is_over() {
+                    frame.draw_text(
+                        &format!("Seed: {}", self.game.seed()),
+                        unselected_color,
+                        center_x,
+                        layout.at(0.5, 0.54).1,
+                        layout.font_size(20.),
+                    );
+
+                    let score = self.game.score_breakdown();
+                    let breakdown = [
+                        format!("Food: {}", score.food()),
+                        format!(
+                            "Streak bonus: {} (best streak {})",
+                            score.streak_bonus(),
+                            score.best_streak()
+                        ),
+                        format!("Time bonus: {}", score.time_bonus()),
+                        format!(
+                            "Total: {} (x{} for {})",
+                            score.total(),
+                            self.game.difficulty().points_multiplier,
+                            self.game.difficulty().name
+                        ),
+                    ];
+                    for (i, line) in breakdown.iter().enumerate() {
+                        frame.draw_text(
+                            line,
+                            unselected_color,
+                            center_x,
+                            layout.at(0.5, 0.58 + 0.033 * i as f32).1,
+                            layout.font_size(20.),
+                        );
+                    }
+                }
+
+                if let Some(name_entry) = &self.name_entry {
+                    frame.draw_text(
+                        "New high score! Type your name with the arrow keys and press enter.",
+                        selected_color,
+                        center_x,
+                        layout.at(0.5, 0.37).1,
+                        layout.font_size(20.),
+                    );
+                    let letter_gap = layout.font_size(40.) as usize;
+                    for (i, letter) in name_entry.name().chars().enumerate() {
+                        let color = if i == name_entry.cursor() {
+                            selected_color
+                        } else {
+                            unselected_color
+                        };
+                        frame.draw_text(
+                            &letter.to_string(),
+                            color,
+                            (center_x + letter_gap * i).saturating_sub(letter_gap),
+                            layout.at(0.5, 0.42).1,
+                            layout.font_size(35.),
+                        );
+                    }
+                }
+
+                match self.game.replay_status() {
+                    None => (),
+                    Some(ReplayStatus::Playing) => {
+                        frame.draw_text(
+                            "Replay",
+                            unselected_color,
+                            center_x,
+                            layout.at(0.5, 0.03).1,
+                            layout.font_size(25.),
+                        );
+                    }
+                    Some(ReplayStatus::Verified) => frame.draw_text(
+                        &format!("Replay verified: score {}", self.game.score()),
+                        theme.success,
+                        center_x,
+                        layout.at(0.5, 0.73).1,
+                        layout.font_size(20.),
+                    ),
+                    Some(ReplayStatus::Mismatch {
+                        expected_score,
+                        score,
+                    }) => frame.draw_text(
+                        &format!("Replay mismatch: expected score {expected_score}, got {score}"),
+                        theme.warning,
+                        center_x,
+                        layout.at(0.5, 0.73).1,
+                        layout.font_size(20.),
+                    ),
+                }
+
+                // Last, so nothing on the board covers it.
+                if self.game.is_paused() {
+                    frame.draw_text(
+                        "Paused",
+                        unselected_color,
+                        center_x,
+                        center_y,
+                        layout.font_size(25.),
+                    );
+                }
+            }
+            GameOrMenu::InMainMenu if matches!(self.menu.menu_type(), MenuType::HighScores) => {
+                let difficulty = self.menu.high_score_difficulty();
+                frame.draw_text(
+                    &format!("High Scores: < {difficulty} >"),
+                    selected_color,
+                    center_x,
+                    layout.at(0.5, 0.125).1,
+                    layout.font_size(35.),
+                );
+                let table = self.high_scores.table(difficulty);
+                if table.is_empty() {
+                    frame.draw_text(
+                        "No games yet",
+                        unselected_color,
+                        center_x,
+                        center_y,
+                        layout.font_size(25.),
+                    );
+                }
+                for (i, entry) in table.iter().enumerate() {
+                    frame.draw_text(
+                        &format!(
+                            "{:>2}. {}  {:>6}  length {:>4}  {:>6}  {}",
+                            i + 1,
+                            entry.name,
+                            entry.score,
+                            entry.length,
+                            entry.duration_text(),
+                            entry.date_text()
+                        ),
+                        unselected_color,
+                        center_x,
+                        layout.at(0.5, 0.25 + 0.04 * i as f32).1,
+                        layout.font_size(22.),
                     );
                 }
             }
             GameOrMenu::InMainMenu => {
-                for (i, currrent_selected_option) in
-                    self.menu.all_possibilities().iter().enumerate()
-                {
+                let possibilities = self.menu.all_possibilities();
+                // Long lists like the custom difficulty settings move up and get tighter so they still fit.
+                let (top, gap) = layout.list(possibilities.len(), 65.);
+                for (i, currrent_selected_option) in possibilities.iter().enumerate() {
                     let color_rgb = if i == self.menu.selected_option() as usize {
                         selected_color
                     } else {
@@ -191,36 +600,15 @@ impl GameWithMenu {
                     frame.draw_text(
                         currrent_selected_option,
                         color_rgb,
-                        frame.width() / 2,
-                        frame.height() / 2 + ((i as u8) * text_gap) as usize,
-                        text_size as f32,
+                        center_x,
+                        top + i * gap,
+                        layout.font_size(50.).min(gap as f32 * 0.75),
                     );
                 }
             }
         }
     }
 }
-/// This function does a transformation from the logic to the graphics and draws the square.
-/// Can draw a square in any color or size
-pub fn draw_snake_square<T: DrawableOn>(
-    frame: &mut T,
-    color: (u8, u8, u8),
-    (square_x, square_y): (usize, usize),
-    (game_square_width, game_square_height): (usize, usize),
-) {
-    let h_s = frame.height() / game_square_height;
-    let w_s = frame.width() / game_square_width;
-    let ix = square_x * w_s;
-    let iy = square_y * h_s;
-    let top_left = (ix as f32, iy as f32);
-
-    frame.fill_rectangle(
-        (w_s, h_s),
-        (color.0, color.1, color.2),
-        (top_left.0 as usize, top_left.1 as usize),
-    );
-}
-
 #[derive(Default, Debug, PartialEq, Clone, Copy)]
 pub enum GameOrMenu {
     InGame,
@@ -232,19 +620,21 @@ pub enum GameOrMenu {
 mod tests {
     use crate::{
         Direction,
-        game::GameDifficulty,
+        clock::ManualClock,
         game_with_menu::{GameOrMenu, GameWithMenu},
+        high_scores::{HighScore, NameEntry},
         menu::SelectedOption,
+        preset::Preset,
     };
 
     #[test]
     fn up_test() {
-        let mut game_with_menu = GameWithMenu::new(GameDifficulty::Normal);
+        let mut game_with_menu = GameWithMenu::new(Preset::default());
         assert_eq!(game_with_menu.game_or_menu, GameOrMenu::InMainMenu);
         game_with_menu.up_pressed();
         assert_eq!(
             game_with_menu.menu.selected_option(),
-            SelectedOption::Settings as u8
+            SelectedOption::HighScores as u8
         );
 
         game_with_menu.game_or_menu = GameOrMenu::InGame;
@@ -254,7 +644,7 @@ mod tests {
 
     #[test]
     fn right_test() {
-        let mut game_with_menu = GameWithMenu::new(GameDifficulty::Normal);
+        let mut game_with_menu = GameWithMenu::new(Preset::default());
         assert_eq!(game_with_menu.game_or_menu, GameOrMenu::InMainMenu);
         game_with_menu.right_pressed();
         assert_eq!(
@@ -268,7 +658,7 @@ mod tests {
     }
     #[test]
     fn left_test() {
-        let mut game_with_menu = GameWithMenu::new(GameDifficulty::Normal);
+        let mut game_with_menu = GameWithMenu::new(Preset::default());
         assert_eq!(game_with_menu.game_or_menu, GameOrMenu::InMainMenu);
         game_with_menu.left_pressed();
         assert_eq!(
@@ -282,7 +672,7 @@ mod tests {
     }
     #[test]
     fn down_test() {
-        let mut game_with_menu = GameWithMenu::new(GameDifficulty::Normal);
+        let mut game_with_menu = GameWithMenu::new(Preset::default());
         assert_eq!(game_with_menu.game_or_menu, GameOrMenu::InMainMenu);
         game_with_menu.down_pressed();
 
@@ -296,8 +686,9 @@ mod tests {
         assert_eq!(game_with_menu.game.direction(), Direction::Down);
     }
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn enter_or_space_test() {
-        let mut game_with_menu = GameWithMenu::new(GameDifficulty::Normal);
+        let mut game_with_menu = GameWithMenu::new(Preset::default());
         assert_eq!(game_with_menu.game_or_menu, GameOrMenu::InMainMenu);
         game_with_menu.enter_or_space_pressed();
         assert_eq!(
@@ -311,4 +702,56 @@ mod tests {
 
         assert_eq!(game_with_menu.game.is_paused(), true);
     }
+
+    #[test]
+    fn tick_test() {
+        let clock = ManualClock::new();
+        let mut game_with_menu = GameWithMenu::new(Preset::default());
+        game_with_menu.game_or_menu = GameOrMenu::InGame;
+        game_with_menu.set_clock(clock.clone());
+        game_with_menu.tick();
+        assert_eq!(game_with_menu.game.tick(), 0);
+
+        clock.advance(Preset::default().timestep());
+        game_with_menu.tick();
+        assert_eq!(game_with_menu.game.tick(), 1);
+    }
+
+    #[test]
+    fn warnings_test() {
+        let mut game_with_menu = GameWithMenu::new(Preset::default());
+        assert!(game_with_menu.take_warnings().is_empty());
+        // A directory cannot be written like a file.
+        game_with_menu.settings_path = Some(std::env::temp_dir());
+        game_with_menu.save_settings();
+        let warnings = game_with_menu.take_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("Cannot save the settings"));
+        assert!(game_with_menu.take_warnings().is_empty());
+    }
+
+    #[test]
+    fn name_entry_test() {
+        let mut game_with_menu = GameWithMenu::new(Preset::default());
+        game_with_menu.name_entry = Some(NameEntry::new(
+            "BOB",
+            HighScore {
+                name: String::new(),
+                score: 7,
+                length: 4,
+                duration: 30,
+                date: 0,
+            },
+            "Normal",
+        ));
+        game_with_menu.right_pressed();
+        game_with_menu.up_pressed();
+        game_with_menu.enter_or_space_pressed();
+        assert!(game_with_menu.name_entry.is_none());
+        assert_eq!(game_with_menu.game_or_menu, GameOrMenu::InMainMenu);
+        let table = game_with_menu.high_scores().table("Normal");
+        assert_eq!(table.len(), 1);
+        assert_eq!(table[0].name, "BPB");
+        assert_eq!(game_with_menu.last_name, "BPB");
+    }
 }
@@ -0,0 +1,289 @@
+//! The best games of every difficulty, kept between runs.
+//!
+//! The table is saved as TOML in the user's data directory, for example
+//! `~/.local/share/snake/high_scores.toml` on Linux.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use web_time::Duration;
+
+/// How many games are kept for every difficulty.
+pub const MAX_ENTRIES: usize = 10;
+/// How many letters a name has.
+pub const NAME_LENGTH: usize = 3;
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+/// One game in the high-score table.
+pub struct HighScore {
+    pub name: String,
+    pub score: usize,
+    /// The length of the snake at the end of the game.
+    pub length: usize,
+    /// How long the game took, in seconds.
+    pub duration: u64,
+    /// When the game was played, in seconds since 1970-01-01 UTC.
+    pub date: u64,
+}
+
+impl HighScore {
+    /// The duration as `minutes:seconds`.
+    pub fn duration_text(&self) -> String {
+        format!("{}:{:02}", self.duration / 60, self.duration % 60)
+    }
+
+    /// The date as `year-month-day`.
+    pub fn date_text(&self) -> String {
+        // Days since 1970-01-01 to a civil date, from Howard Hinnant's `civil_from_days`.
+        let days = (self.date / 86_400) as i64 + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+        let month = if month_index < 10 {
+            month_index + 3
+        } else {
+            month_index - 9
+        };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        format!("{year:04}-{month:02}-{day:02}")
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+/// The top [`MAX_ENTRIES`] games of every difficulty, best first.
+pub struct HighScores {
+    #[serde(default)]
+    tables: BTreeMap<String, Vec<HighScore>>,
+}
+
+impl HighScores {
+    /// Where the table is saved, or [`None`] if the platform has no data directory (like the web).
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("snake").join("high_scores.toml"))
+    }
+
+    /// Loads the table from `path`. A missing file gives an empty table.
+    pub fn load(path: impl AsRef<Path>) -> Result<HighScores, HighScoreError> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text).map_err(HighScoreError::Invalid),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(HighScores::default()),
+            Err(error) => Err(HighScoreError::Io(error)),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), HighScoreError> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(HighScoreError::Io)?;
+        }
+        let text = toml::to_string(self).map_err(HighScoreError::Serialize)?;
+        std::fs::write(path, text).map_err(HighScoreError::Io)
+    }
+
+    /// The games of the difficulty called `difficulty`, best first.
+    pub fn table(&self, difficulty: &str) -> &[HighScore] {
+        self.tables.get(difficulty).map_or(&[], Vec::as_slice)
+    }
+
+    /// Gives [`true`] if a game with `score` would get into the table of `difficulty`.
+    pub fn qualifies(&self, difficulty: &str, score: usize) -> bool {
+        let table = self.table(difficulty);
+        score > 0 && (table.len() < MAX_ENTRIES || table.iter().any(|entry| score > entry.score))
+    }
+
+    /// Puts `entry` into the table of `difficulty` and gives its place, starting at 0.
+    /// Gives [`None`] if it is not good enough.
+    pub fn insert(&mut self, difficulty: &str, entry: HighScore) -> Option<usize> {
+        if !self.qualifies(difficulty, entry.score) {
+            return None;
+        }
+        let table = self.tables.entry(difficulty.to_string()).or_default();
+        // Older games stay in front of newer ones with the same score.
+        let place = table
+            .iter()
+            .position(|other| entry.score > other.score)
+            .unwrap_or(table.len());
+        table.insert(place, entry);
+        table.truncate(MAX_ENTRIES);
+        Some(place)
+    }
+}
+
+#[derive(Debug)]
+/// This enum tells why the high scores could not be loaded or saved.
+pub enum HighScoreError {
+    Io(std::io::Error),
+    Invalid(toml::de::Error),
+    Serialize(toml::ser::Error),
+}
+
+impl fmt::Display for HighScoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HighScoreError::Io(error) => write!(f, "{error}"),
+            HighScoreError::Invalid(error) => write!(f, "the high scores are invalid: {error}"),
+            HighScoreError::Serialize(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for HighScoreError {}
+
+#[derive(Debug, PartialEq, Clone)]
+/// Typing a name with the arrow keys, like on an arcade machine.
+/// Up and down change the letter under the cursor, left and right move the cursor.
+pub struct NameEntry {
+    letters: [u8; NAME_LENGTH],
+    cursor: usize,
+    /// The game that is waiting for its name.
+    pub(crate) entry: HighScore,
+    /// The name of the difficulty the game was played with.
+    pub(crate) difficulty: String,
+}
+
+impl NameEntry {
+    /// Starts with `name`, so a player does not have to type their name again every game.
+    pub(crate) fn new(name: &str, entry: HighScore, difficulty: &str) -> Self {
+        let mut letters = [b'A'; NAME_LENGTH];
+        for (letter, character) in letters.iter_mut().zip(name.bytes()) {
+            if character.is_ascii_uppercase() {
+                *letter = character;
+            }
+        }
+        NameEntry {
+            letters,
+            cursor: 0,
+            entry,
+            difficulty: difficulty.to_string(),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        String::from_utf8_lossy(&self.letters).into_owned()
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn next_letter(&mut self) {
+        let letter = &mut self.letters[self.cursor];
+        *letter = if *letter == b'Z' { b'A' } else { *letter + 1 };
+    }
+
+    pub fn previous_letter(&mut self) {
+        let letter = &mut self.letters[self.cursor];
+        *letter = if *letter == b'A' { b'Z' } else { *letter - 1 };
+    }
+
+    pub fn cursor_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn cursor_right(&mut self) {
+        self.cursor = (self.cursor + 1).min(NAME_LENGTH - 1);
+    }
+
+    /// The finished game with the typed name.
+    pub(crate) fn finish(self) -> (String, HighScore) {
+        let name = self.name();
+        (self.difficulty, HighScore { name, ..self.entry })
+    }
+}
+
+/// Seconds since 1970-01-01 UTC.
+pub(crate) fn now() -> u64 {
+    web_time::SystemTime::now()
+        .duration_since(web_time::SystemTime::UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(score: usize) -> HighScore {
+        HighScore {
+            name: "ABC".to_string(),
+            score,
+            length: 5,
+            duration: 125,
+            date: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn table() {
+        let mut high_scores = HighScores::default();
+        assert!(!high_scores.qualifies("Easy", 0));
+        assert_eq!(high_scores.insert("Easy", entry(5)), Some(0));
+        assert_eq!(high_scores.insert("Easy", entry(9)), Some(0));
+        assert_eq!(high_scores.insert("Easy", entry(5)), Some(2));
+        assert!(high_scores.table("Hard").is_empty());
+
+        for score in 10..20 {
+            high_scores.insert("Easy", entry(score));
+        }
+        let table = high_scores.table("Easy");
+        assert_eq!(table.len(), MAX_ENTRIES);
+        assert_eq!(table[0].score, 19);
+        assert_eq!(table[MAX_ENTRIES - 1].score, 10);
+        assert!(!high_scores.qualifies("Easy", 10));
+        assert_eq!(high_scores.insert("Easy", entry(3)), None);
+
+        let text = toml::to_string(&high_scores).unwrap();
+        assert_eq!(toml::from_str::<HighScores>(&text).unwrap(), high_scores);
+        assert_eq!(
+            toml::from_str::<HighScores>("").unwrap(),
+            HighScores::default()
+        );
+    }
+
+    #[test]
+    fn text() {
+        assert_eq!(entry(1).duration_text(), "2:05");
+        assert_eq!(entry(1).date_text(), "2023-11-14");
+        assert_eq!(
+            HighScore {
+                date: 0,
+                ..entry(1)
+            }
+            .date_text(),
+            "1970-01-01"
+        );
+        assert_eq!(
+            HighScore {
+                date: 951_782_400,
+                ..entry(1)
+            }
+            .date_text(),
+            "2000-02-29"
+        );
+    }
+
+    #[test]
+    fn name_entry() {
+        let mut name_entry = NameEntry::new("Zo", entry(1), "Easy");
+        assert_eq!(name_entry.name(), "ZAA");
+        name_entry.next_letter();
+        assert_eq!(name_entry.name(), "AAA");
+        name_entry.cursor_left();
+        name_entry.cursor_right();
+        name_entry.cursor_right();
+        name_entry.cursor_right();
+        assert_eq!(name_entry.cursor(), NAME_LENGTH - 1);
+        name_entry.previous_letter();
+        assert_eq!(name_entry.name(), "AAZ");
+        let (difficulty, finished) = name_entry.finish();
+        assert_eq!(difficulty, "Easy");
+        assert_eq!(finished.name, "AAZ");
+        assert_eq!(finished.score, 1);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::food::FoodKind;
+
 #[derive(Debug, PartialEq, Default, Clone)]
 #[must_use]
 /// This enum tells wether the game is over or not
@@ -5,6 +7,8 @@ pub enum GameResult {
     #[default]
     NoOp,
     GameOver,
+    /// The snake covers every free square of the board. The player won.
+    BoardCleared,
 }
 
 impl GameResult {
@@ -12,6 +16,56 @@ impl GameResult {
     /// If the game is over, it gives [`true`].
     /// If the game is not over, it gives [`false`].
     pub fn is_over(&self) -> bool {
-        *self == GameResult::GameOver
+        matches!(self, GameResult::GameOver | GameResult::BoardCleared)
+    }
+
+    /// Gives [`true`] if the game is over because the player won.
+    pub fn is_victory(&self) -> bool {
+        *self == GameResult::BoardCleared
     }
 }
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// This enum tells what the snake ran into.
+pub enum DeathCause {
+    /// The edge of the board.
+    Wall,
+    /// An obstacle of the arena.
+    Obstacle,
+    /// Its own body.
+    Snake,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// Something that happened during a logic step. Frontends can use these for sounds, particles and statistics.
+pub enum GameEvent {
+    /// The head of the snake moved to `head`.
+    Moved { head: (usize, usize) },
+    /// The snake ate food of the given kind at `position` and is now `new_length` squares long.
+    FoodEaten {
+        position: (usize, usize),
+        kind: FoodKind,
+        new_length: usize,
+    },
+    /// Food that was not eaten in time disappeared from `position`.
+    FoodExpired {
+        position: (usize, usize),
+        kind: FoodKind,
+    },
+    /// The snake ate shrink food and is now `length` squares long.
+    Shrank { length: usize },
+    /// The effect of speed-up, slow-down or golden food is over.
+    EffectEnded { kind: FoodKind },
+    /// The tail stayed where it was, so the snake is now `length` squares long.
+    Grew { length: usize },
+    /// The snake has grown everything it ate and moves normally again.
+    GrowthFinished { length: usize },
+    /// The snake covers every free square of the board, so the game is won.
+    BoardCleared { length: usize },
+    /// The game is over. For [`DeathCause::Wall`], `cell` is the last square before the edge,
+    /// otherwise it is the square the snake ran into.
+    Died {
+        cause: DeathCause,
+        cell: (usize, usize),
+    },
+}
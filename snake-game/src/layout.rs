@@ -0,0 +1,251 @@
+//! Where the board and the HUD go on a frame.
+//!
+//! Both frontends draw through [`GameWithMenu::draw`](crate::game_with_menu::GameWithMenu::draw),
+//! which computes a [`Layout`] from the size of the frame every time. The squares of the board are
+//! always square, the board is centered above the HUD band and it gets a border around it.
+//!
+//! Text is placed with fractions of the frame and its size is given for a frame of
+//! [`REFERENCE_SIZE`], so it is scaled with the frame from tiny windows to 4K.
+
+use crate::traits::DrawableOn;
+
+/// How much of the height of the frame the HUD band at the bottom takes.
+pub const HUD_FRACTION: f32 = 0.1;
+/// The HUD band is never lower than this, unless the frame itself is too small.
+pub const MIN_HUD_HEIGHT: usize = 30;
+/// The thickness of the border around the board in pixels.
+pub const BORDER: usize = 2;
+/// The space between the border and the edges of the board area in pixels.
+pub const MARGIN: usize = 8;
+/// Squares smaller than this get no grid lines between them.
+pub const MIN_GRID_CELL: usize = 6;
+/// The size of frame that font sizes are given for.
+pub const REFERENCE_SIZE: (usize, usize) = (1000, 750);
+/// Text is never drawn smaller than this, so it stays readable in tiny windows.
+pub const MIN_FONT_SIZE: f32 = 8.;
+/// How much of the height of the frame a list of text lines may use at most.
+pub const MAX_LIST_FRACTION: f32 = 0.9;
+/// How tall a line of text is compared to its font size. Both frontends lay text out with it, so
+/// text of the same size takes the same room in both.
+pub const LINE_HEIGHT: f32 = 1.2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The pixel positions of the board and the HUD band on a frame.
+pub struct Layout {
+    /// The width and height of one square of the board.
+    cell: usize,
+    /// The top left pixel of the first square of the board.
+    board_top_left: (usize, usize),
+    /// The size of the board in squares.
+    board_size: (usize, usize),
+    /// The first pixel row of the HUD band.
+    hud_top: usize,
+    /// The size of the frame.
+    frame_size: (usize, usize),
+}
+
+impl Layout {
+    /// Computes the layout of a board of `board_size` squares on a frame of `frame_size` pixels.
+    pub fn new(frame_size: (usize, usize), board_size: (usize, usize)) -> Layout {
+        let (frame_width, frame_height) = frame_size;
+        let (board_width, board_height) = (board_size.0.max(1), board_size.1.max(1));
+
+        let hud_height = ((frame_height as f32 * HUD_FRACTION) as usize)
+            .max(MIN_HUD_HEIGHT)
+            .min(frame_height / 2);
+        let hud_top = frame_height - hud_height;
+
+        let available_width = frame_width.saturating_sub(2 * (MARGIN + BORDER));
+        let available_height = hud_top.saturating_sub(2 * (MARGIN + BORDER));
+        let cell = (available_width / board_width)
+            .min(available_height / board_height)
+            .max(1);
+
+        let board_top_left = (
+            frame_width.saturating_sub(cell * board_width) / 2,
+            hud_top.saturating_sub(cell * board_height) / 2,
+        );
+
+        Layout {
+            cell,
+            board_top_left,
+            board_size: (board_width, board_height),
+            hud_top,
+            frame_size,
+        }
+    }
+
+    /// Computes the layout for the current size of `frame`.
+    pub fn of_frame<T: DrawableOn>(frame: &T, board_size: (usize, usize)) -> Layout {
+        Layout::new((frame.width(), frame.height()), board_size)
+    }
+
+    /// The width and height of one square of the board in pixels.
+    pub fn cell(&self) -> usize {
+        self.cell
+    }
+
+    /// The top left pixel of the board.
+    pub fn board_top_left(&self) -> (usize, usize) {
+        self.board_top_left
+    }
+
+    /// The width and height of the board in pixels.
+    pub fn board_pixels(&self) -> (usize, usize) {
+        (self.cell * self.board_size.0, self.cell * self.board_size.1)
+    }
+
+    /// The top left pixel of a square of the board. The square can be anywhere between squares.
+    pub fn square_top_left(&self, (square_x, square_y): (f32, f32)) -> (usize, usize) {
+        let x = self.board_top_left.0 as f32 + square_x * self.cell as f32;
+        let y = self.board_top_left.1 as f32 + square_y * self.cell as f32;
+        (x.round().max(0.) as usize, y.round().max(0.) as usize)
+    }
+
+    /// The first pixel row and the height of the HUD band at the bottom of the frame.
+    pub fn hud(&self) -> (usize, usize) {
+        (self.hud_top, self.frame_size.1 - self.hud_top)
+    }
+
+    /// The pixel in the middle of the HUD band, horizontally at `x_fraction` of the frame.
+    pub fn hud_point(&self, x_fraction: f32) -> (usize, usize) {
+        let (top, height) = self.hud();
+        (self.at(x_fraction, 0.).0, top + height / 2)
+    }
+
+    /// The pixel at `x_fraction` of the width and `y_fraction` of the height of the frame.
+    pub fn at(&self, x_fraction: f32, y_fraction: f32) -> (usize, usize) {
+        (
+            (self.frame_size.0 as f32 * x_fraction) as usize,
+            (self.frame_size.1 as f32 * y_fraction) as usize,
+        )
+    }
+
+    /// How much bigger the frame is than [`REFERENCE_SIZE`], without stretching text.
+    pub fn scale(&self) -> f32 {
+        (self.frame_size.0 as f32 / REFERENCE_SIZE.0 as f32)
+            .min(self.frame_size.1 as f32 / REFERENCE_SIZE.1 as f32)
+    }
+
+    /// The font size for text that is `reference_size` big on a frame of [`REFERENCE_SIZE`].
+    pub fn font_size(&self, reference_size: f32) -> f32 {
+        (reference_size * self.scale()).max(MIN_FONT_SIZE)
+    }
+
+    /// The first pixel row and the gap between the rows of a list of `count` lines, which are
+    /// `reference_gap` apart on a frame of [`REFERENCE_SIZE`].
+    ///
+    /// The list starts in the middle of the frame and moves up and gets tighter when it is too
+    /// long to fit.
+    pub fn list(&self, count: usize, reference_gap: f32) -> (usize, usize) {
+        let height = self.frame_size.1;
+        let max_gap = (height as f32 * MAX_LIST_FRACTION / count.max(1) as f32) as usize;
+        let gap = ((reference_gap * self.scale()) as usize)
+            .min(max_gap)
+            .max(1);
+        let top = (height / 2)
+            .min(height.saturating_sub(count * gap))
+            .max(gap / 2);
+        (top, gap)
+    }
+
+    /// Draws one pixel wide lines between the squares of the board, if they are big enough to
+    /// have room for them.
+    pub fn draw_grid<T: DrawableOn>(&self, frame: &mut T, color: (u8, u8, u8)) {
+        if self.cell < MIN_GRID_CELL {
+            return;
+        }
+        let (left, top) = self.board_top_left;
+        let (width, height) = self.board_pixels();
+        for x in 1..self.board_size.0 {
+            frame.fill_rectangle((1, height), color, (left + x * self.cell, top));
+        }
+        for y in 1..self.board_size.1 {
+            frame.fill_rectangle((width, 1), color, (left, top + y * self.cell));
+        }
+    }
+
+    /// Draws the border around the board, just outside of its squares.
+    pub fn draw_border<T: DrawableOn>(&self, frame: &mut T, color: (u8, u8, u8)) {
+        let (left, top) = self.board_top_left;
+        let (width, height) = self.board_pixels();
+        let outer_left = left.saturating_sub(BORDER);
+        let outer_top = top.saturating_sub(BORDER);
+        let outer_width = left + width + BORDER - outer_left;
+
+        frame.fill_rectangle(
+            (outer_width, top - outer_top),
+            color,
+            (outer_left, outer_top),
+        );
+        frame.fill_rectangle((outer_width, BORDER), color, (outer_left, top + height));
+        frame.fill_rectangle((left - outer_left, height), color, (outer_left, top));
+        frame.fill_rectangle((BORDER, height), color, (left + width, top));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bevy_window() {
+        let layout = Layout::new((1000, 600), (25, 25));
+        assert_eq!(layout.cell(), 20);
+        assert_eq!(layout.board_pixels(), (500, 500));
+        assert_eq!(layout.hud(), (540, 60));
+        // Centered horizontally and in the space above the HUD band.
+        assert_eq!(layout.board_top_left(), (250, 20));
+        assert_eq!(layout.square_top_left((1.5, 2.)), (280, 60));
+        assert_eq!(layout.hud_point(0.5), (500, 570));
+    }
+
+    #[test]
+    fn fits_in_frame() {
+        for frame_size in [
+            (1000, 600),
+            (600, 1000),
+            (1920, 1080),
+            (3840, 2160),
+            (120, 90),
+        ] {
+            for board_size in [(5, 5), (8, 8), (25, 25), (150, 60), (60, 150)] {
+                let layout = Layout::new(frame_size, board_size);
+                let (left, top) = layout.board_top_left();
+                let (width, height) = layout.board_pixels();
+                assert!(layout.cell() >= 1);
+                if layout.cell() > 1 {
+                    assert!(left >= BORDER && left + width + BORDER <= frame_size.0);
+                    assert!(top >= BORDER && top + height + BORDER <= layout.hud().0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tiny_frame() {
+        let layout = Layout::new((10, 10), (100, 100));
+        assert_eq!(layout.cell(), 1);
+        assert_eq!(layout.hud(), (5, 5));
+        assert_eq!(layout.font_size(50.), MIN_FONT_SIZE);
+    }
+
+    #[test]
+    fn relative_text() {
+        let reference = Layout::new(REFERENCE_SIZE, (25, 25));
+        assert_eq!(reference.font_size(25.), 25.);
+        assert_eq!(reference.at(0.5, 0.5), (500, 375));
+        assert_eq!(reference.list(4, 65.), (375, 65));
+
+        let uhd = Layout::new((3840, 2160), (25, 25));
+        assert!((uhd.font_size(25.) - 72.).abs() < 0.01);
+        assert_eq!(uhd.at(0.5, 0.5), (1920, 1080));
+
+        // The bevy window is less tall than the reference, so text gets smaller to fit.
+        let bevy = Layout::new((1000, 600), (25, 25));
+        assert_eq!(bevy.font_size(50.), 40.);
+        let (top, gap) = bevy.list(10, 65.);
+        assert_eq!(gap, 52);
+        assert!(top + 10 * gap <= 600);
+    }
+}
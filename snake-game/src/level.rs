@@ -0,0 +1,342 @@
+//! Arenas written as plain text.
+//!
+//! Every line of the file is one row of the board and every character is one square:
+//!
+//! * `.` is an empty square.
+//! * `#` is a wall. Running into it is game over.
+//! * `F` is a fixed food spawn. When a level has food spawns, food only appears on them.
+//! * `S` is where the snake starts. It waits for the first key press like on a random board.
+//! * `^`, `v`, `<` and `>` are also where the snake starts, but it is already moving up, down, left or right.
+//!
+//! ```text
+//! #######
+//! #..F..#
+//! #.....#
+//! #..>..#
+//! #######
+//! ```
+//!
+//! [`Level::load`] reads a level from a file and [`str::parse`] from text.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::Direction;
+use crate::snakelogic::SnakeLogic;
+
+#[derive(Debug, PartialEq, Clone)]
+/// An arena loaded from text. Use [`SnakeLogic::from_level`] to play it.
+pub struct Level {
+    width: usize,
+    height: usize,
+    obstacles: BTreeSet<(usize, usize)>,
+    start: (usize, usize),
+    start_direction: Direction,
+    food_spawns: Vec<(usize, usize)>,
+}
+
+impl Level {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn obstacles(&self) -> &BTreeSet<(usize, usize)> {
+        &self.obstacles
+    }
+
+    /// The square where the snake starts.
+    pub fn start(&self) -> (usize, usize) {
+        self.start
+    }
+
+    /// The direction the snake is moving in when the game starts.
+    pub fn start_direction(&self) -> Direction {
+        self.start_direction
+    }
+
+    pub fn food_spawns(&self) -> &[(usize, usize)] {
+        &self.food_spawns
+    }
+
+    /// Loads the level from the text file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Level, LevelError> {
+        std::fs::read_to_string(path)
+            .map_err(|error| LevelError::Io(error.kind()))?
+            .parse()
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+/// This enum tells why a level could not be loaded.
+/// Lines and columns start at 1, like in a text editor.
+pub enum LevelError {
+    /// The file could not be read.
+    Io(std::io::ErrorKind),
+    /// The text does not contain a single row.
+    Empty,
+    /// A character that is not part of the level format.
+    InvalidCharacter {
+        line: usize,
+        column: usize,
+        character: char,
+    },
+    /// A row that is not as long as the first one.
+    RaggedLine {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The rows are shorter than [`SnakeLogic::MIN_WIDTH`] or longer than [`SnakeLogic::MAX_WIDTH`].
+    InvalidWidth { line: usize, width: usize },
+    /// There are less rows than [`SnakeLogic::MIN_HEIGHT`] or more than [`SnakeLogic::MAX_HEIGHT`].
+    InvalidHeight { height: usize },
+    /// There is no `S`, `^`, `v`, `<` or `>` in the level.
+    MissingStart,
+    /// A second snake start. The first one is in `first`.
+    DuplicateStart {
+        line: usize,
+        column: usize,
+        first: (usize, usize),
+    },
+    /// Every square is a wall or the snake start, so there is nowhere to put food.
+    NoFreeSquare,
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelError::Io(kind) => write!(f, "the level cannot be read: {kind}"),
+            LevelError::Empty => write!(f, "the level is empty"),
+            LevelError::InvalidCharacter {
+                line,
+                column,
+                character,
+            } => write!(
+                f,
+                "line {line}, column {column}: unexpected character {character:?}"
+            ),
+            LevelError::RaggedLine {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {line}: expected {expected} squares like the first line but found {found}"
+            ),
+            LevelError::InvalidWidth { line, width } => write!(
+                f,
+                "line {line}: the level is {width} squares wide but must be between {} and {}",
+                SnakeLogic::MIN_WIDTH,
+                SnakeLogic::MAX_WIDTH
+            ),
+            LevelError::InvalidHeight { height } => write!(
+                f,
+                "the level is {height} squares high but must be between {} and {}",
+                SnakeLogic::MIN_HEIGHT,
+                SnakeLogic::MAX_HEIGHT
+            ),
+            LevelError::MissingStart => write!(f, "the level has no snake start"),
+            LevelError::DuplicateStart {
+                line,
+                column,
+                first,
+            } => write!(
+                f,
+                "line {line}, column {column}: second snake start, the first one is at line {}, column {}",
+                first.1 + 1,
+                first.0 + 1
+            ),
+            LevelError::NoFreeSquare => write!(f, "the level has no free square for food"),
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+impl FromStr for Level {
+    type Err = LevelError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let rows: Vec<&str> = text.trim_end().lines().map(|l| l.trim_end()).collect();
+        let first_row = rows.first().ok_or(LevelError::Empty)?;
+        let width = first_row.chars().count();
+        if !(SnakeLogic::MIN_WIDTH..=SnakeLogic::MAX_WIDTH).contains(&width) {
+            return Err(LevelError::InvalidWidth { line: 1, width });
+        }
+        let height = rows.len();
+        if !(SnakeLogic::MIN_HEIGHT..=SnakeLogic::MAX_HEIGHT).contains(&height) {
+            return Err(LevelError::InvalidHeight { height });
+        }
+
+        let mut obstacles = BTreeSet::new();
+        let mut food_spawns = Vec::new();
+        let mut start: Option<((usize, usize), Direction)> = None;
+
+        for (y, row) in rows.iter().enumerate() {
+            let found = row.chars().count();
+            if found != width {
+                return Err(LevelError::RaggedLine {
+                    line: y + 1,
+                    expected: width,
+                    found,
+                });
+            }
+
+            for (x, character) in row.chars().enumerate() {
+                let start_direction = match character {
+                    '.' => continue,
+                    '#' => {
+                        obstacles.insert((x, y));
+                        continue;
+                    }
+                    'F' => {
+                        food_spawns.push((x, y));
+                        continue;
+                    }
+                    'S' => Direction::None,
+                    '^' => Direction::Up,
+                    'v' => Direction::Down,
+                    '<' => Direction::Left,
+                    '>' => Direction::Right,
+                    _ => {
+                        return Err(LevelError::InvalidCharacter {
+                            line: y + 1,
+                            column: x + 1,
+                            character,
+                        });
+                    }
+                };
+
+                if let Some((first, _)) = start {
+                    return Err(LevelError::DuplicateStart {
+                        line: y + 1,
+                        column: x + 1,
+                        first,
+                    });
+                }
+                start = Some(((x, y), start_direction));
+            }
+        }
+
+        let (start, start_direction) = start.ok_or(LevelError::MissingStart)?;
+        if obstacles.len() + 1 == width * height {
+            return Err(LevelError::NoFreeSquare);
+        }
+
+        Ok(Level {
+            width,
+            height,
+            obstacles,
+            start,
+            start_direction,
+            food_spawns,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::food::Food;
+
+    #[test]
+    fn parse() {
+        let level: Level = "#######\n\
+                            #..F..#\n\
+                            #.....#\n\
+                            #..>..#\n\
+                            #######\n"
+            .parse()
+            .unwrap();
+        assert_eq!(level.width(), 7);
+        assert_eq!(level.height(), 5);
+        assert_eq!(level.start(), (3, 3));
+        assert_eq!(level.start_direction(), Direction::Right);
+        assert_eq!(level.food_spawns(), [(3, 1)]);
+        assert_eq!(level.obstacles().len(), 7 * 2 + 3 * 2);
+        assert!(level.obstacles().contains(&(0, 2)));
+        assert!(!level.obstacles().contains(&(1, 2)));
+
+        let logic = SnakeLogic::from_level(&level, 0);
+        assert_eq!(*logic.snake(), [(3, 3)]);
+        assert_eq!(logic.direction(), Direction::Right);
+        assert_eq!(logic.food(), [Food::from((3, 1))]);
+        assert_eq!(logic.obstacles(), level.obstacles());
+    }
+
+    #[test]
+    fn load() {
+        let path = std::env::temp_dir().join(format!("snake-level-{}.txt", std::process::id()));
+        std::fs::write(&path, "#####\n#S..#\n#...#\n#..F#\n#####\n").unwrap();
+        let level = Level::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        let level = level.unwrap();
+        assert_eq!(level.start(), (1, 1));
+        assert_eq!(level.food_spawns(), [(3, 3)]);
+
+        assert_eq!(
+            Level::load(std::env::temp_dir().join("snake-no-such-level.txt")),
+            Err(LevelError::Io(std::io::ErrorKind::NotFound))
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!("".parse::<Level>(), Err(LevelError::Empty));
+        assert_eq!(
+            "....\n....\n....\n....\n..S.".parse::<Level>(),
+            Err(LevelError::InvalidWidth { line: 1, width: 4 })
+        );
+        assert_eq!(
+            ".....\n.....\n..S..\n.....".parse::<Level>(),
+            Err(LevelError::InvalidHeight { height: 4 })
+        );
+        assert_eq!(
+            ".....\n.....\n..S..\n....\n.....".parse::<Level>(),
+            Err(LevelError::RaggedLine {
+                line: 4,
+                expected: 5,
+                found: 4
+            })
+        );
+        assert_eq!(
+            ".....\n.....\n..S..\n...x.\n.....".parse::<Level>(),
+            Err(LevelError::InvalidCharacter {
+                line: 4,
+                column: 4,
+                character: 'x'
+            })
+        );
+        assert_eq!(
+            ".....\n.....\n.....\n.....\n.....".parse::<Level>(),
+            Err(LevelError::MissingStart)
+        );
+        assert_eq!(
+            ".....\n.S...\n.....\n...<.\n.....".parse::<Level>(),
+            Err(LevelError::DuplicateStart {
+                line: 4,
+                column: 4,
+                first: (1, 1)
+            })
+        );
+        assert_eq!(
+            "#####\n#####\n##S##\n#####\n#####".parse::<Level>(),
+            Err(LevelError::NoFreeSquare)
+        );
+        assert_eq!(
+            LevelError::InvalidCharacter {
+                line: 4,
+                column: 4,
+                character: 'x'
+            }
+            .to_string(),
+            "line 4, column 4: unexpected character 'x'"
+        );
+    }
+}
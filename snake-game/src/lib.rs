@@ -1,11 +1,24 @@
-mod game;
+pub mod clock;
+pub mod food;
+pub mod game;
 pub mod game_with_menu;
+pub mod high_scores;
 mod internal;
+pub mod layout;
+pub mod level;
 mod menu;
-mod snakelogic;
+mod occupancy;
+pub mod preset;
+pub mod render;
+pub mod replay;
+pub mod score;
+pub mod snakelogic;
+pub mod theme;
 pub mod traits;
 
-#[derive(Clone, Debug, Copy, PartialEq)]
+pub use internal::{DeathCause, GameEvent, GameResult};
+
+#[derive(Clone, Debug, Copy, PartialEq, strum::Display, strum::EnumString)]
 /// This enum gives the direction.
 pub enum Direction {
     Up,
@@ -15,3 +28,33 @@ pub enum Direction {
     /// This is when you aren't moving
     None,
 }
+
+#[derive(
+    Clone,
+    Debug,
+    Copy,
+    PartialEq,
+    Default,
+    strum::Display,
+    strum::EnumString,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+/// This enum tells what happens when the snake reaches the edge of the board.
+pub enum WallMode {
+    /// Hitting the edge of the board is game over.
+    #[default]
+    Solid,
+    /// The snake comes back in on the opposite side of the board.
+    WrapAround,
+}
+
+impl WallMode {
+    /// Switches between [`WallMode::Solid`] and [`WallMode::WrapAround`].
+    pub fn toggle(&mut self) {
+        *self = match self {
+            WallMode::Solid => WallMode::WrapAround,
+            WallMode::WrapAround => WallMode::Solid,
+        }
+    }
+}
@@ -1,16 +1,30 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
 use strum::{EnumCount, EnumIter, FromRepr, IntoEnumIterator};
 
+use crate::game::GameOptions;
+use crate::preset::{CUSTOM, DEFAULT_PRESET, Preset, Presets};
+use crate::theme::{DEFAULT_THEME, Theme, Themes};
+
 #[derive(Debug, Default, Clone, Copy)]
 pub enum MenuType {
     #[default]
     MainMenu,
     SettingsMenu,
+    /// The table of the best games of one difficulty.
+    HighScores,
 }
 #[derive(Debug, Default)]
 pub struct Menu {
     selected_option: SelectedOption,
     setting: Settings,
     menu_type: MenuType,
+    /// The name of the difficulty whose high scores are shown.
+    high_score_difficulty: String,
+    presets: Presets,
+    themes: Themes,
 }
 
 pub enum MenuAction {
@@ -22,15 +36,113 @@ impl Menu {
     pub fn selected_option(&self) -> u8 {
         match self.menu_type {
             MenuType::MainMenu => self.selected_option as u8,
-            MenuType::SettingsMenu => self.setting.selected_setting as u8,
+            MenuType::SettingsMenu => self
+                .setting
+                .visible_settings()
+                .iter()
+                .position(|setting| *setting == self.setting.selected_setting)
+                .unwrap_or_default() as u8,
+            MenuType::HighScores => 0,
         }
     }
 
+    pub fn high_score_difficulty(&self) -> &str {
+        &self.high_score_difficulty
+    }
+
+    pub fn next_high_score_difficulty(&mut self) {
+        self.high_score_difficulty = self.presets.next_name(&self.high_score_difficulty);
+    }
+
+    pub fn previous_high_score_difficulty(&mut self) {
+        self.high_score_difficulty = self.presets.previous_name(&self.high_score_difficulty);
+    }
+
     pub fn next_difficulty(&mut self) {
-        self.setting.next_difficulty();
+        self.setting.difficulty = self.presets.next_name(&self.setting.difficulty);
     }
     pub fn previous_difficulty(&mut self) {
-        self.setting.previous_difficulty();
+        self.setting.difficulty = self.presets.previous_name(&self.setting.difficulty);
+    }
+
+    /// The difficulty a new game starts with.
+    pub fn game_difficulty(&self) -> Preset {
+        match self.presets.get(&self.setting.difficulty) {
+            Some(preset) => preset.clone(),
+            None => self.setting.custom.clone(),
+        }
+    }
+
+    /// Replaces the presets to choose from. A chosen preset that is gone falls back to a default one.
+    pub fn set_presets(&mut self, presets: Presets) {
+        self.presets = presets;
+        self.setting.difficulty = self.presets.known_name(&self.setting.difficulty);
+    }
+
+    pub fn next_theme(&mut self) {
+        self.setting.theme = self.themes.next_name(&self.setting.theme);
+    }
+    pub fn previous_theme(&mut self) {
+        self.setting.theme = self.themes.previous_name(&self.setting.theme);
+    }
+
+    /// The chosen theme.
+    pub fn theme(&self) -> &Theme {
+        self.themes.get_or_first(&self.setting.theme)
+    }
+
+    /// Replaces the themes to choose from. A chosen theme that is gone falls back to a default one.
+    pub fn set_themes(&mut self, themes: Themes) {
+        self.themes = themes;
+        self.setting.theme = self.themes.known_name(&self.setting.theme);
+    }
+
+    pub fn toggle_wall_mode(&mut self) {
+        self.setting.options.wall_mode.toggle();
+    }
+
+    pub fn next_food_count(&mut self) {
+        self.setting.options.next_food_count();
+    }
+
+    pub fn previous_food_count(&mut self) {
+        self.setting.options.previous_food_count();
+    }
+
+    pub fn toggle_speed_ramp(&mut self) {
+        self.setting.options.speed_ramp = !self.setting.options.speed_ramp;
+    }
+
+    pub fn toggle_smooth_movement(&mut self) {
+        self.setting.smooth_movement = !self.setting.smooth_movement;
+    }
+
+    pub fn toggle_body_gradient(&mut self) {
+        self.setting.body_gradient = !self.setting.body_gradient;
+    }
+
+    /// Increases the value of `setting` if it is one of the custom difficulty.
+    pub fn next_custom_value(&mut self, setting: SelectedSetting) {
+        let custom = &mut self.setting.custom;
+        match setting {
+            SelectedSetting::Width => custom.next_width(),
+            SelectedSetting::Height => custom.next_height(),
+            SelectedSetting::TickDuration => custom.next_tick_millis(),
+            SelectedSetting::Growth => custom.next_growth(),
+            _ => (),
+        }
+    }
+
+    /// Decreases the value of `setting` if it is one of the custom difficulty.
+    pub fn previous_custom_value(&mut self, setting: SelectedSetting) {
+        let custom = &mut self.setting.custom;
+        match setting {
+            SelectedSetting::Width => custom.previous_width(),
+            SelectedSetting::Height => custom.previous_height(),
+            SelectedSetting::TickDuration => custom.previous_tick_millis(),
+            SelectedSetting::Growth => custom.previous_growth(),
+            _ => (),
+        }
     }
 
     #[cfg(test)]
@@ -39,6 +151,9 @@ impl Menu {
             selected_option: SelectedOption::default(),
             setting: Settings::default(),
             menu_type: MenuType::default(),
+            high_score_difficulty: DEFAULT_PRESET.to_string(),
+            presets: Presets::builtin(),
+            themes: Themes::builtin(),
         }
     }
 
@@ -46,8 +161,17 @@ impl Menu {
         self.menu_type
     }
 
-    pub fn settings(&self) -> Settings {
-        self.setting
+    pub fn settings(&self) -> &Settings {
+        &self.setting
+    }
+
+    pub fn set_settings(&mut self, settings: Settings) {
+        self.setting = Settings {
+            selected_setting: self.setting.selected_setting,
+            difficulty: self.presets.known_name(&settings.difficulty),
+            theme: self.themes.known_name(&settings.theme),
+            ..settings
+        };
     }
 
     pub fn all_possibilities(&self) -> Vec<String> {
@@ -62,6 +186,7 @@ impl Menu {
         match self.menu_type {
             MenuType::MainMenu => all_options,
             MenuType::SettingsMenu => all_settings,
+            MenuType::HighScores => Vec::new(),
         }
     }
 
@@ -74,13 +199,36 @@ impl Menu {
                     self.set_menu_type(MenuType::SettingsMenu);
                     MenuAction::NoOp
                 }
+                SelectedOption::HighScores => {
+                    self.high_score_difficulty = self.setting.difficulty.clone();
+                    self.set_menu_type(MenuType::HighScores);
+                    MenuAction::NoOp
+                }
                 SelectedOption::NewGame => MenuAction::NewGame,
             },
+            MenuType::HighScores => {
+                self.set_menu_type(MenuType::MainMenu);
+                MenuAction::NoOp
+            }
             MenuType::SettingsMenu => {
                 match self.selected_setting() {
                     SelectedSetting::Difficulty => {
-                        self.setting.difficulty.next_difficulty();
+                        self.next_difficulty();
+                    }
+                    SelectedSetting::WallMode => {
+                        self.setting.options.wall_mode.toggle();
                     }
+                    SelectedSetting::FoodCount => {
+                        self.setting.options.next_food_count();
+                    }
+                    SelectedSetting::SpeedRamp => self.toggle_speed_ramp(),
+                    SelectedSetting::SmoothMovement => self.toggle_smooth_movement(),
+                    SelectedSetting::Theme => self.next_theme(),
+                    SelectedSetting::BodyGradient => self.toggle_body_gradient(),
+                    setting @ (SelectedSetting::Width
+                    | SelectedSetting::Height
+                    | SelectedSetting::TickDuration
+                    | SelectedSetting::Growth) => self.next_custom_value(setting),
                     SelectedSetting::Back => {
                         self.set_menu_type(MenuType::MainMenu);
                     }
@@ -93,14 +241,16 @@ impl Menu {
     pub fn select_next_option(&mut self) {
         match self.menu_type {
             MenuType::MainMenu => self.selected_option.next_selection(),
-            MenuType::SettingsMenu => self.setting.selected_setting.next_selection(),
+            MenuType::SettingsMenu => self.setting.next_setting(),
+            MenuType::HighScores => (),
         }
     }
 
     pub fn select_previous_option(&mut self) {
         match self.menu_type {
             MenuType::MainMenu => self.selected_option.previous_selection(),
-            MenuType::SettingsMenu => self.setting.selected_setting.previous_selection(),
+            MenuType::SettingsMenu => self.setting.previous_setting(),
+            MenuType::HighScores => (),
         }
     }
 
@@ -117,43 +267,178 @@ pub enum SelectedOption {
     #[default]
     NewGame,
     Settings,
+    HighScores,
 }
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// The settings are saved as TOML. Missing keys get their default and unknown keys are ignored,
+/// so files written by older or newer versions still load.
+#[serde(default)]
 pub struct Settings {
+    #[serde(skip)]
     selected_setting: SelectedSetting,
-    difficulty: Difficulty,
+    /// The name of the chosen preset or [`CUSTOM`].
+    difficulty: String,
+    options: GameOptions,
+    /// The rules used when the difficulty is [`CUSTOM`].
+    custom: Preset,
+    /// Draws the snake gliding between squares instead of jumping from one to the next.
+    smooth_movement: bool,
+    /// The name of the chosen color theme.
+    theme: String,
+    /// Fades the body of the snake from the head to the tail with the colors of the theme.
+    body_gradient: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            selected_setting: SelectedSetting::default(),
+            difficulty: DEFAULT_PRESET.to_string(),
+            options: GameOptions::default(),
+            custom: Preset::default(),
+            smooth_movement: true,
+            theme: DEFAULT_THEME.to_string(),
+            body_gradient: true,
+        }
+    }
 }
 
 impl Settings {
-    pub fn difficulty(&self) -> Difficulty {
-        self.difficulty
+    /// Where the settings are saved, or [`None`] if the platform has no config directory (like the web).
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("snake").join("settings.toml"))
     }
 
-    pub fn next_difficulty(&mut self) {
-        self.difficulty.next_difficulty();
+    /// Loads the settings from `path`. A missing file gives the default settings.
+    pub fn load(path: impl AsRef<Path>) -> Result<Settings, SettingsError> {
+        let mut settings: Settings = match std::fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text).map_err(SettingsError::Invalid)?,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Settings::default(),
+            Err(error) => return Err(SettingsError::Io(error)),
+        };
+        settings.options.food_count = settings
+            .options
+            .food_count
+            .clamp(1, GameOptions::MAX_FOOD_COUNT);
+        settings.options.ramp = settings.options.ramp.clamped();
+        settings.custom = settings.custom.into_custom();
+        Ok(settings)
     }
 
-    pub fn previous_difficulty(&mut self) {
-        self.difficulty.previous_difficulty();
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SettingsError> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(SettingsError::Io)?;
+        }
+        let text = toml::to_string(self).map_err(SettingsError::Serialize)?;
+        std::fs::write(path, text).map_err(SettingsError::Io)
     }
 
-    pub fn all_possibilities(&self) -> Vec<String> {
+    /// The name of the chosen preset or [`CUSTOM`].
+    pub fn difficulty(&self) -> &str {
+        &self.difficulty
+    }
+
+    pub fn options(&self) -> GameOptions {
+        self.options
+    }
+
+    pub fn smooth_movement(&self) -> bool {
+        self.smooth_movement
+    }
+
+    pub fn body_gradient(&self) -> bool {
+        self.body_gradient
+    }
+
+    /// The settings shown in the menu. The rules of the custom difficulty are only shown while it is chosen.
+    pub fn visible_settings(&self) -> Vec<SelectedSetting> {
         SelectedSetting::all_possibilities()
+            .into_iter()
+            .filter(|setting| self.difficulty() == CUSTOM || !setting.is_custom())
+            .collect()
+    }
+
+    pub fn next_setting(&mut self) {
+        self.selected_setting.next_selection();
+        while !self.visible_settings().contains(&self.selected_setting) {
+            self.selected_setting.next_selection();
+        }
+    }
+
+    pub fn previous_setting(&mut self) {
+        self.selected_setting.previous_selection();
+        while !self.visible_settings().contains(&self.selected_setting) {
+            self.selected_setting.previous_selection();
+        }
+    }
+
+    pub fn all_possibilities(&self) -> Vec<String> {
+        self.visible_settings()
             .iter()
             .map(|t| match t {
-                SelectedSetting::Difficulty => format!("Difficulty: {:?}", self.difficulty),
+                SelectedSetting::Difficulty => format!("Difficulty: {}", self.difficulty),
+                SelectedSetting::Width => format!("Width: {}", self.custom.width),
+                SelectedSetting::Height => format!("Height: {}", self.custom.height),
+                SelectedSetting::TickDuration => format!("Tick: {} ms", self.custom.tick_millis),
+                SelectedSetting::Growth => format!("Growth: {}", self.custom.growth),
+                SelectedSetting::WallMode => format!("Wall mode: {:?}", self.options.wall_mode),
+                SelectedSetting::FoodCount => format!("Food: {}", self.options.food_count),
+                SelectedSetting::SpeedRamp => format!(
+                    "Speed ramp: {}",
+                    if self.options.speed_ramp { "On" } else { "Off" }
+                ),
+                SelectedSetting::SmoothMovement => format!(
+                    "Smooth movement: {}",
+                    if self.smooth_movement { "On" } else { "Off" }
+                ),
+                SelectedSetting::Theme => format!("Theme: {}", self.theme),
+                SelectedSetting::BodyGradient => format!(
+                    "Body gradient: {}",
+                    if self.body_gradient { "On" } else { "Off" }
+                ),
                 SelectedSetting::Back => "Back".to_string(),
             })
             .collect()
     }
 }
 
+#[derive(Debug)]
+/// This enum tells why the settings could not be loaded or saved.
+pub enum SettingsError {
+    Io(std::io::Error),
+    Invalid(toml::de::Error),
+    Serialize(toml::ser::Error),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::Io(error) => write!(f, "{error}"),
+            SettingsError::Invalid(error) => write!(f, "the settings are invalid: {error}"),
+            SettingsError::Serialize(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
 #[derive(Debug, Clone, Copy, PartialEq, Default, FromRepr, EnumCount, EnumIter)]
 #[repr(u8)]
 pub enum SelectedSetting {
     #[default]
     Difficulty,
+    Width,
+    Height,
+    TickDuration,
+    Growth,
+    WallMode,
+    FoodCount,
+    SpeedRamp,
+    SmoothMovement,
+    Theme,
+    BodyGradient,
     Back,
 }
 
@@ -162,6 +447,17 @@ impl SelectedSetting {
         SelectedSetting::iter().collect()
     }
 
+    /// Gives [`true`] for the settings that only belong to the custom difficulty.
+    pub fn is_custom(&self) -> bool {
+        matches!(
+            self,
+            SelectedSetting::Width
+                | SelectedSetting::Height
+                | SelectedSetting::TickDuration
+                | SelectedSetting::Growth
+        )
+    }
+
     pub fn next_selection(&mut self) {
         if Self::COUNT - 1 == *self as usize {
             return *self = Self::from_repr(0).expect("Cannot Fail");
@@ -178,36 +474,6 @@ impl SelectedSetting {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, FromRepr, EnumCount, EnumIter, Default)]
-pub enum Difficulty {
-    VeryEasy,
-    Easy,
-    #[default]
-    Normal,
-    Intermediate,
-    Hard,
-    Expert,
-    Extreme,
-    Insane,
-    Basic,
-    VeryHard,
-}
-
-impl Difficulty {
-    pub fn next_difficulty(&mut self) {
-        if Self::COUNT - 1 == *self as usize {
-            return *self = Self::from_repr(0).expect("Cannot Fail");
-        }
-        *self = Self::from_repr(((*self as u8) + 1) as usize).expect("Cannot Fail")
-    }
-
-    pub fn previous_difficulty(&mut self) {
-        if 0 == *self as usize {
-            return *self = Self::from_repr(Self::COUNT - 1).expect("Cannot Fail");
-        }
-        *self = Self::from_repr(((*self as u8) - 1) as usize).expect("Cannot Fail")
-    }
-}
 impl SelectedOption {
     pub fn next_selection(&mut self) {
         if Self::COUNT - 1 == *self as usize {
@@ -227,13 +493,18 @@ impl SelectedOption {
         match self {
             SelectedOption::NewGame => "New Game",
             SelectedOption::Settings => "Settings",
+            SelectedOption::HighScores => "High Scores",
         }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::menu::SelectedOption;
+    use crate::WallMode;
+    use crate::game::GameOptions;
+    use crate::menu::{Menu, MenuType, SelectedOption, SelectedSetting, Settings};
+    use crate::preset::{CUSTOM, Preset};
+    use crate::theme::DEFAULT_THEME;
 
     #[test]
     fn all() {
@@ -242,16 +513,140 @@ mod test {
         option.next_selection();
         assert_eq!(option, SelectedOption::Settings);
         option.next_selection();
-        assert_eq!(option, SelectedOption::NewGame);
+        assert_eq!(option, SelectedOption::HighScores);
         option.next_selection();
-        assert_eq!(option, SelectedOption::Settings);
-        option.previous_selection();
         assert_eq!(option, SelectedOption::NewGame);
         option.previous_selection();
+        assert_eq!(option, SelectedOption::HighScores);
+        option.previous_selection();
         assert_eq!(option, SelectedOption::Settings);
         option.previous_selection();
         assert_eq!(option, SelectedOption::NewGame);
         option.previous_selection();
-        assert_eq!(option, SelectedOption::Settings);
+        assert_eq!(option, SelectedOption::HighScores);
+    }
+
+    #[test]
+    fn settings_file() {
+        let mut settings = Settings {
+            difficulty: "Hard".to_string(),
+            ..Settings::default()
+        };
+        settings.options.wall_mode.toggle();
+        settings.options.next_food_count();
+        let text = toml::to_string(&settings).unwrap();
+        assert_eq!(toml::from_str::<Settings>(&text).unwrap(), settings);
+
+        let settings: Settings = toml::from_str(
+            "difficulty = \"Hard\"\nvolume = 3\n[options]\nwall_mode = \"WrapAround\"\n",
+        )
+        .unwrap();
+        assert_eq!(settings.difficulty(), "Hard");
+        assert_eq!(settings.options().wall_mode, WallMode::WrapAround);
+        assert_eq!(settings.options().food_count, 1);
+        assert_eq!(toml::from_str::<Settings>("").unwrap(), Settings::default());
+
+        let mut menu = Menu::new();
+        menu.set_settings(toml::from_str("difficulty = \"Removed\"\n").unwrap());
+        assert_eq!(menu.settings().difficulty(), "Normal");
+    }
+
+    #[test]
+    fn load_clamps() {
+        let path = std::env::temp_dir().join(format!("snake-settings-{}.toml", std::process::id()));
+        std::fs::write(
+            &path,
+            "[options]\nfood_count = 50\n[options.ramp]\nstart_millis = 0\nfloor_millis = 0\n",
+        )
+        .unwrap();
+        let settings = Settings::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let options = settings.options();
+        assert_eq!(options.food_count, GameOptions::MAX_FOOD_COUNT);
+        assert_eq!(options.ramp.start_millis, Some(Preset::MIN_TICK_MILLIS));
+        assert_eq!(options.ramp.floor_millis, Preset::MIN_TICK_MILLIS);
+        assert!(options.ramp.is_valid());
+    }
+
+    #[test]
+    fn themes() {
+        let mut menu = Menu::new();
+        assert_eq!(menu.theme().name, DEFAULT_THEME);
+        menu.set_menu_type(MenuType::SettingsMenu);
+        while menu.selected_setting() != SelectedSetting::Theme {
+            menu.select_next_option();
+        }
+        menu.enter_or_space_pressed();
+        assert_ne!(menu.theme().name, DEFAULT_THEME);
+        assert_eq!(
+            menu.all_possibilities()[menu.selected_option() as usize],
+            format!("Theme: {}", menu.theme().name)
+        );
+        menu.previous_theme();
+        assert_eq!(menu.theme().name, DEFAULT_THEME);
+        assert!(menu.settings().body_gradient());
+        menu.select_next_option();
+        menu.enter_or_space_pressed();
+        assert!(!menu.settings().body_gradient());
+
+        menu.set_settings(toml::from_str("theme = \"Removed\"\n").unwrap());
+        assert_eq!(menu.theme().name, DEFAULT_THEME);
+        menu.set_settings(toml::from_str("theme = \"HighContrast\"\n").unwrap());
+        assert_eq!(menu.theme().name, "HighContrast");
+    }
+
+    #[test]
+    fn difficulty_order() {
+        let mut menu = Menu::new();
+        let mut names = Vec::new();
+        for _ in 0..menu.presets.names().len() {
+            menu.next_difficulty();
+            names.push(menu.settings().difficulty().to_string());
+        }
+        assert_eq!(
+            names,
+            [
+                "Hard",
+                "Intermediate",
+                "Extreme",
+                "VeryHard",
+                "Insane",
+                "Expert",
+                CUSTOM,
+                "VeryEasy",
+                "Easy",
+                "Basic",
+                "Normal"
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_settings() {
+        let mut menu = Menu::new();
+        menu.set_menu_type(MenuType::SettingsMenu);
+        menu.select_next_option();
+        assert_eq!(menu.selected_setting(), SelectedSetting::WallMode);
+        menu.select_previous_option();
+        assert_eq!(menu.all_possibilities().len(), 8);
+
+        for _ in 0..4 {
+            menu.previous_difficulty();
+        }
+        assert_eq!(menu.settings().difficulty(), CUSTOM);
+        assert_eq!(menu.all_possibilities().len(), 12);
+        menu.select_next_option();
+        assert_eq!(menu.selected_setting(), SelectedSetting::Width);
+        assert_eq!(menu.selected_option(), 1);
+        menu.next_custom_value(SelectedSetting::Width);
+        menu.select_next_option();
+        menu.select_next_option();
+        menu.enter_or_space_pressed();
+        assert_eq!(menu.all_possibilities()[1], "Width: 30");
+        assert_eq!(menu.all_possibilities()[3], "Tick: 110 ms");
+        let difficulty = menu.game_difficulty();
+        assert!(difficulty.is_custom());
+        assert_eq!(difficulty.width, 30);
     }
 }
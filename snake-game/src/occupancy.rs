@@ -0,0 +1,146 @@
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// What is on a square of the board.
+pub enum Square {
+    /// Nothing. The number is where the square is in the list of free squares.
+    Free(usize),
+    Snake,
+    Obstacle,
+    Food,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+/// **A grid that knows what is on every square of the board, with a list of the free squares next to it.
+/// Looking up a square, taking it, freeing it and picking a random free square are all O(1).**
+pub struct Occupancy {
+    width: usize,
+    height: usize,
+    squares: Vec<Square>,
+    free: Vec<(usize, usize)>,
+}
+
+impl Occupancy {
+    /// Creates a grid where every square is free.
+    pub fn new(width: usize, height: usize) -> Self {
+        let free: Vec<(usize, usize)> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .collect();
+        let squares = (0..free.len()).map(Square::Free).collect();
+        Occupancy {
+            width,
+            height,
+            squares,
+            free,
+        }
+    }
+
+    /// Gives what is on `square`, or [`None`] if it is outside of the board.
+    pub fn get(&self, (x, y): (usize, usize)) -> Option<Square> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        Some(self.squares[y * self.width + x])
+    }
+
+    pub fn is_free(&self, square: (usize, usize)) -> bool {
+        matches!(self.get(square), Some(Square::Free(_)))
+    }
+
+    /// Puts `what` (the snake, an obstacle or food) on `square`. Squares outside of the board are ignored.
+    pub fn set(&mut self, square: (usize, usize), what: Square) {
+        debug_assert!(!matches!(what, Square::Free(_)), "use Occupancy::free");
+        if let Some(Square::Free(index)) = self.get(square) {
+            self.free.swap_remove(index);
+            if let Some(&moved) = self.free.get(index) {
+                let moved_index = self.index(moved);
+                self.squares[moved_index] = Square::Free(index);
+            }
+        }
+        if let Some(index) = self.checked_index(square) {
+            self.squares[index] = what;
+        }
+    }
+
+    /// Frees `square` again. Squares outside of the board are ignored.
+    pub fn free(&mut self, square: (usize, usize)) {
+        match self.get(square) {
+            None | Some(Square::Free(_)) => (),
+            Some(_) => {
+                let index = self.index(square);
+                self.squares[index] = Square::Free(self.free.len());
+                self.free.push(square);
+            }
+        }
+    }
+
+    pub fn free_squares(&self) -> &[(usize, usize)] {
+        &self.free
+    }
+
+    /// Picks one of the free squares, every one equally likely. Gives [`None`] if the board is full.
+    pub fn random_free(&self, rng: &mut ChaCha8Rng) -> Option<(usize, usize)> {
+        if self.free.is_empty() {
+            return None;
+        }
+        Some(self.free[rng.random_range(0..self.free.len())])
+    }
+
+    fn checked_index(&self, (x, y): (usize, usize)) -> Option<usize> {
+        (x < self.width && y < self.height).then(|| y * self.width + x)
+    }
+
+    fn index(&self, (x, y): (usize, usize)) -> usize {
+        y * self.width + x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+
+    #[test]
+    fn set_and_free() {
+        let mut occupancy = Occupancy::new(5, 5);
+        assert_eq!(occupancy.free_squares().len(), 25);
+        assert_eq!(occupancy.get((5, 0)), None);
+
+        occupancy.set((0, 0), Square::Snake);
+        occupancy.set((4, 4), Square::Obstacle);
+        occupancy.set((9, 9), Square::Snake);
+        assert_eq!(occupancy.get((0, 0)), Some(Square::Snake));
+        assert_eq!(occupancy.get((4, 4)), Some(Square::Obstacle));
+        assert!(occupancy.is_free((1, 0)));
+        assert_eq!(occupancy.free_squares().len(), 23);
+
+        occupancy.free((0, 0));
+        occupancy.free((0, 0));
+        occupancy.free((9, 9));
+        assert!(occupancy.is_free((0, 0)));
+        assert_eq!(occupancy.free_squares().len(), 24);
+
+        // Every free square knows where it is in the list.
+        for (index, square) in occupancy.free_squares().iter().enumerate() {
+            assert_eq!(occupancy.get(*square), Some(Square::Free(index)));
+        }
+    }
+
+    #[test]
+    fn random_free() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut occupancy = Occupancy::new(5, 5);
+        for y in 0..5 {
+            for x in 0..5 {
+                if (x, y) != (2, 3) {
+                    occupancy.set((x, y), Square::Snake);
+                }
+            }
+        }
+        assert_eq!(occupancy.random_free(&mut rng), Some((2, 3)));
+        occupancy.set((2, 3), Square::Snake);
+        assert_eq!(occupancy.random_free(&mut rng), None);
+    }
+}
@@ -0,0 +1,445 @@
+//! Difficulty presets: the board size, speed and growth a game is played with.
+//!
+//! The built-in presets are in `data/presets.toml`. Presets in a file with the same layout in the
+//! user's config directory are added to them and replace a built-in preset with the same name,
+//! so adding a preset is adding one entry:
+//!
+//! ```toml
+//! [[preset]]
+//! name = "Normal"
+//! order = 4
+//! width = 25
+//! height = 25
+//! tick_millis = 100
+//! growth = 4
+//! points_multiplier = 2
+//! ```
+//!
+//! Next to the presets there is always the custom difficulty, whose rules the player
+//! chooses in the settings menu.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use web_time::Duration;
+
+use crate::snakelogic::{DEFAULT_GROWTH, SnakeLogic};
+
+/// The name of the custom difficulty. No preset can have it.
+pub const CUSTOM: &str = "Custom";
+/// The preset chosen when the settings name none that exists.
+pub const DEFAULT_PRESET: &str = "Normal";
+
+const BUILTIN_PRESETS: &str = include_str!("../data/presets.toml");
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+/// The rules of a game: how big the board is, how fast the snake moves and how much it grows.
+pub struct Preset {
+    pub name: String,
+    /// Presets are sorted by this, from the easiest to the hardest.
+    pub order: u32,
+    pub width: usize,
+    pub height: usize,
+    /// The time between two logic steps, in milliseconds.
+    pub tick_millis: u64,
+    /// How many squares the snake grows for every food it eats.
+    pub growth: usize,
+    /// What all points are multiplied by. Faster and bigger games are worth more.
+    pub points_multiplier: usize,
+}
+
+impl Preset {
+    /// How much the board width and height change with one key press.
+    pub const SIZE_STEP: usize = 5;
+    pub const MIN_TICK_MILLIS: u64 = 10;
+    pub const MAX_TICK_MILLIS: u64 = 500;
+    /// How much the tick duration changes with one key press.
+    pub const TICK_MILLIS_STEP: u64 = 10;
+    pub const MIN_GROWTH: usize = 1;
+    pub const MAX_GROWTH: usize = 10;
+
+    pub fn timestep(&self) -> Duration {
+        Duration::from_millis(self.tick_millis)
+    }
+
+    pub fn game_size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    pub fn is_custom(&self) -> bool {
+        self.name == CUSTOM
+    }
+
+    /// Gives [`true`] if every value is within its bounds.
+    pub fn is_valid(&self) -> bool {
+        *self == self.clone().clamped()
+    }
+
+    /// Brings every value back within its bounds.
+    pub fn clamped(self) -> Preset {
+        Preset {
+            width: self
+                .width
+                .clamp(SnakeLogic::MIN_WIDTH, SnakeLogic::MAX_WIDTH),
+            height: self
+                .height
+                .clamp(SnakeLogic::MIN_HEIGHT, SnakeLogic::MAX_HEIGHT),
+            tick_millis: self
+                .tick_millis
+                .clamp(Self::MIN_TICK_MILLIS, Self::MAX_TICK_MILLIS),
+            growth: self.growth.clamp(Self::MIN_GROWTH, Self::MAX_GROWTH),
+            points_multiplier: self.points_multiplier.max(1),
+            ..self
+        }
+    }
+
+    /// Keeps the board size, speed and growth within their bounds and makes it the custom difficulty,
+    /// which always comes last and has no points multiplier.
+    pub fn into_custom(self) -> Preset {
+        Preset {
+            name: CUSTOM.to_string(),
+            order: u32::MAX,
+            points_multiplier: 1,
+            ..self.clamped()
+        }
+    }
+
+    pub fn next_width(&mut self) {
+        self.width = (self.width + Self::SIZE_STEP).min(SnakeLogic::MAX_WIDTH);
+    }
+
+    pub fn previous_width(&mut self) {
+        self.width = self
+            .width
+            .saturating_sub(Self::SIZE_STEP)
+            .max(SnakeLogic::MIN_WIDTH);
+    }
+
+    pub fn next_height(&mut self) {
+        self.height = (self.height + Self::SIZE_STEP).min(SnakeLogic::MAX_HEIGHT);
+    }
+
+    pub fn previous_height(&mut self) {
+        self.height = self
+            .height
+            .saturating_sub(Self::SIZE_STEP)
+            .max(SnakeLogic::MIN_HEIGHT);
+    }
+
+    pub fn next_tick_millis(&mut self) {
+        self.tick_millis = (self.tick_millis + Self::TICK_MILLIS_STEP).min(Self::MAX_TICK_MILLIS);
+    }
+
+    pub fn previous_tick_millis(&mut self) {
+        self.tick_millis = self
+            .tick_millis
+            .saturating_sub(Self::TICK_MILLIS_STEP)
+            .max(Self::MIN_TICK_MILLIS);
+    }
+
+    pub fn next_growth(&mut self) {
+        self.growth = (self.growth + 1).min(Self::MAX_GROWTH);
+    }
+
+    pub fn previous_growth(&mut self) {
+        self.growth = self.growth.saturating_sub(1).max(Self::MIN_GROWTH);
+    }
+}
+
+impl PartialEq for Preset {
+    /// Presets are equal if they have the same name and rules. The [`Preset::order`] only sorts
+    /// the menu, and replays don't keep it.
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.width == other.width
+            && self.height == other.height
+            && self.tick_millis == other.tick_millis
+            && self.growth == other.growth
+            && self.points_multiplier == other.points_multiplier
+    }
+}
+
+impl Default for Preset {
+    /// The custom difficulty before the player changed anything.
+    fn default() -> Self {
+        Preset {
+            name: CUSTOM.to_string(),
+            order: u32::MAX,
+            width: 25,
+            height: 25,
+            tick_millis: 100,
+            growth: DEFAULT_GROWTH,
+            points_multiplier: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// All presets, sorted from the easiest to the hardest.
+pub struct Presets {
+    #[serde(rename = "preset", default)]
+    presets: Vec<Preset>,
+}
+
+impl Presets {
+    /// The presets that come with the game.
+    pub fn builtin() -> Presets {
+        BUILTIN_PRESETS
+            .parse()
+            .expect("The built-in presets are valid")
+    }
+
+    /// Where the user's presets are read from, or [`None`] if the platform has no config directory.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("snake").join("presets.toml"))
+    }
+
+    /// Loads the built-in presets together with the presets in `path`, sorted by their order.
+    /// A missing file gives only the built-in presets.
+    pub fn load(path: impl AsRef<Path>) -> Result<Presets, PresetError> {
+        let mut presets = Presets::builtin();
+        let user_presets: Presets = match std::fs::read_to_string(path) {
+            Ok(text) => text.parse()?,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(presets),
+            Err(error) => return Err(PresetError::Io(error)),
+        };
+        for preset in user_presets.presets {
+            match presets
+                .presets
+                .iter_mut()
+                .find(|other| other.name == preset.name)
+            {
+                Some(builtin) => *builtin = preset,
+                None => presets.presets.push(preset),
+            }
+        }
+        presets.presets.sort_by_key(|preset| preset.order);
+        Ok(presets)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Preset> {
+        self.presets.iter()
+    }
+
+    /// The preset called `name`. The custom difficulty is not one of them.
+    pub fn get(&self, name: &str) -> Option<&Preset> {
+        self.presets.iter().find(|preset| preset.name == name)
+    }
+
+    /// The names to choose from in the menu: all presets in order and then [`CUSTOM`].
+    pub fn names(&self) -> Vec<&str> {
+        self.presets
+            .iter()
+            .map(|preset| preset.name.as_str())
+            .chain([CUSTOM])
+            .collect()
+    }
+
+    /// The name after `name` in [`Presets::names`], going back to the first one after the last.
+    pub fn next_name(&self, name: &str) -> String {
+        let names = self.names();
+        let index = names.iter().position(|other| *other == name);
+        let next = index.map_or(0, |index| (index + 1) % names.len());
+        names[next].to_string()
+    }
+
+    /// The name before `name` in [`Presets::names`], going to the last one before the first.
+    pub fn previous_name(&self, name: &str) -> String {
+        let names = self.names();
+        let index = names.iter().position(|other| *other == name);
+        let previous = index.map_or(0, |index| (index + names.len() - 1) % names.len());
+        names[previous].to_string()
+    }
+
+    /// `name` if there is a preset with it or it is [`CUSTOM`], otherwise [`DEFAULT_PRESET`] or the first preset.
+    pub fn known_name(&self, name: &str) -> String {
+        if name == CUSTOM || self.get(name).is_some() {
+            name.to_string()
+        } else if self.get(DEFAULT_PRESET).is_some() {
+            DEFAULT_PRESET.to_string()
+        } else {
+            self.presets[0].name.clone()
+        }
+    }
+}
+
+impl Default for Presets {
+    fn default() -> Self {
+        Presets::builtin()
+    }
+}
+
+impl FromStr for Presets {
+    type Err = PresetError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut presets: Presets = toml::from_str(text).map_err(PresetError::Invalid)?;
+        if presets.presets.is_empty() {
+            return Err(PresetError::Empty);
+        }
+        for (index, preset) in presets.presets.iter().enumerate() {
+            if preset.name.is_empty()
+                || preset.name.contains(char::is_whitespace)
+                || preset.is_custom()
+                || !preset.is_valid()
+            {
+                return Err(PresetError::InvalidPreset(preset.name.clone()));
+            }
+            if presets.presets[..index]
+                .iter()
+                .any(|other| other.name == preset.name)
+            {
+                return Err(PresetError::DuplicateName(preset.name.clone()));
+            }
+        }
+        presets.presets.sort_by_key(|preset| preset.order);
+        Ok(presets)
+    }
+}
+
+#[derive(Debug)]
+/// This enum tells why presets could not be loaded.
+pub enum PresetError {
+    Io(std::io::Error),
+    Invalid(toml::de::Error),
+    /// The file has no presets at all.
+    Empty,
+    /// The preset has no name, a name with spaces, the name [`CUSTOM`] or values out of bounds.
+    InvalidPreset(String),
+    DuplicateName(String),
+}
+
+impl fmt::Display for PresetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PresetError::Io(error) => write!(f, "{error}"),
+            PresetError::Invalid(error) => write!(f, "the presets are invalid: {error}"),
+            PresetError::Empty => write!(f, "there are no presets"),
+            PresetError::InvalidPreset(name) => write!(f, "the preset {name:?} is invalid"),
+            PresetError::DuplicateName(name) => {
+                write!(f, "there is more than one preset called {name:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PresetError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin() {
+        let presets = Presets::builtin();
+        let orders: Vec<u32> = presets.iter().map(|preset| preset.order).collect();
+        assert!(orders.is_sorted());
+        // A harder preset is never worth fewer points.
+        let multipliers: Vec<usize> = presets
+            .iter()
+            .map(|preset| preset.points_multiplier)
+            .collect();
+        assert!(multipliers.is_sorted());
+        assert!(
+            presets
+                .iter()
+                .zip(presets.iter().skip(1))
+                .all(|(easier, harder)| easier.tick_millis > harder.tick_millis)
+        );
+        assert_eq!(presets.get("Easy").unwrap().game_size(), (15, 15));
+        assert_eq!(
+            presets.get("Normal").unwrap().timestep(),
+            Duration::from_millis(100)
+        );
+        assert!(presets.get(CUSTOM).is_none());
+
+        assert_eq!(presets.next_name("VeryEasy"), "Easy");
+        assert_eq!(presets.next_name("Expert"), CUSTOM);
+        assert_eq!(presets.next_name(CUSTOM), "VeryEasy");
+        assert_eq!(presets.previous_name("VeryEasy"), CUSTOM);
+        assert_eq!(presets.known_name("Gone"), DEFAULT_PRESET);
+        assert_eq!(presets.known_name(CUSTOM), CUSTOM);
+    }
+
+    #[test]
+    fn data_file() {
+        let presets: Presets =
+            "[[preset]]\nname = \"B\"\norder = 2\nwidth = 10\nheight = 10\ntick_millis = 50\n\
+             [[preset]]\nname = \"A\"\norder = 1\nwidth = 20\nheight = 5\ntick_millis = 80\n"
+                .parse()
+                .unwrap();
+        assert_eq!(presets.names(), ["A", "B", CUSTOM]);
+        assert_eq!(presets.get("A").unwrap().growth, DEFAULT_GROWTH);
+        assert_eq!(presets.known_name("Normal"), "A");
+
+        assert!(matches!("".parse::<Presets>(), Err(PresetError::Empty)));
+        assert!(matches!(
+            "[[preset]]\nname = \"A\"\n[[preset]]\nname = \"A\"\n".parse::<Presets>(),
+            Err(PresetError::DuplicateName(_))
+        ));
+        assert!(matches!(
+            "[[preset]]\nname = \"Big\"\nwidth = 1000\n".parse::<Presets>(),
+            Err(PresetError::InvalidPreset(_))
+        ));
+        assert!(matches!(
+            "[[preset]]\nwidth = 10\n".parse::<Presets>(),
+            Err(PresetError::InvalidPreset(_))
+        ));
+    }
+
+    #[test]
+    fn user_file() {
+        let path = std::env::temp_dir().join(format!("snake-presets-{}.toml", std::process::id()));
+        std::fs::write(
+            &path,
+            "[[preset]]\nname = \"Tiny\"\norder = 0\nwidth = 5\nheight = 5\n\
+             [[preset]]\nname = \"Normal\"\norder = 4\nwidth = 30\nheight = 30\ntick_millis = 90\n",
+        )
+        .unwrap();
+        let presets = Presets::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(presets.names()[0], "Tiny");
+        assert_eq!(presets.get("Normal").unwrap().game_size(), (30, 30));
+        assert_eq!(presets.get("Expert"), Presets::builtin().get("Expert"));
+        assert_eq!(
+            presets.iter().count(),
+            Presets::builtin().iter().count() + 1
+        );
+        let orders: Vec<u32> = presets.iter().map(|preset| preset.order).collect();
+        assert!(orders.is_sorted());
+
+        assert_eq!(
+            Presets::load(std::env::temp_dir().join("snake-no-such-presets.toml")).unwrap(),
+            Presets::builtin()
+        );
+    }
+
+    #[test]
+    fn custom() {
+        let mut custom = Preset::default();
+        custom.next_width();
+        custom.previous_height();
+        custom.next_growth();
+        for _ in 0..100 {
+            custom.previous_tick_millis();
+        }
+        assert!(custom.is_valid());
+        assert_eq!(custom.game_size(), (30, 20));
+        assert_eq!(custom.tick_millis, Preset::MIN_TICK_MILLIS);
+
+        let too_big = Preset {
+            width: 1000,
+            points_multiplier: 6,
+            ..Presets::builtin().get("Hard").unwrap().clone()
+        };
+        assert!(!too_big.is_valid());
+        let custom = too_big.into_custom();
+        assert!(custom.is_custom());
+        assert_eq!(custom.width, SnakeLogic::MAX_WIDTH);
+        assert_eq!(custom.points_multiplier, 1);
+    }
+}
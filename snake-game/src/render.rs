@@ -0,0 +1,258 @@
+//! Drawing the snake: a head with eyes that looks the way the snake goes, rounded corners where
+//! it turns, a tail that gets thinner and a body that fades along the theme's gradient.
+//!
+//! When the squares of the board are too small to see any of that, the snake is drawn as plain
+//! squares.
+
+use crate::Direction;
+use crate::layout::Layout;
+use crate::theme::{Rgb, Theme};
+use crate::traits::{DrawableOn, Rgba};
+
+/// Squares smaller than this are drawn plain.
+pub const MIN_DETAIL_CELL: usize = 6;
+/// How many squares at the end of the tail get thinner.
+pub const TAPER_SEGMENTS: usize = 3;
+/// How thick the last square of the tail is, compared to a whole square.
+pub const THINNEST_TAIL: f32 = 0.5;
+/// How much of a square the corners of the body are rounded with.
+pub const BODY_ROUNDING: f32 = 0.35;
+/// How much of a square the corners of the head are rounded with.
+pub const HEAD_ROUNDING: f32 = 0.45;
+
+/// Draws the snake from `squares`, which go from the tail to the head and can be between
+/// squares of the board. The head looks the way the last two squares go, or at
+/// `fallback_direction` if they don't tell. Without `gradient` the whole body has one color.
+pub fn draw_snake<T: DrawableOn>(
+    frame: &mut T,
+    squares: &[(f32, f32)],
+    fallback_direction: Direction,
+    theme: &Theme,
+    gradient: bool,
+    layout: &Layout,
+) {
+    let length = squares.len();
+    let Some(&head) = squares.last() else {
+        return;
+    };
+    let body_length = length - 1;
+    let colors: Vec<Rgb> = (0..body_length)
+        .map(|i| {
+            if gradient {
+                theme.body(body_length - 1 - i, body_length)
+            } else {
+                theme.body
+            }
+        })
+        .chain([theme.head])
+        .collect();
+
+    if layout.cell() < MIN_DETAIL_CELL {
+        for (square, color) in squares.iter().zip(&colors) {
+            draw_square_at(frame, *color, *square, layout);
+        }
+        return;
+    }
+
+    let cell = layout.cell() as f32;
+    let thickness = |i: usize| {
+        if i == body_length || i >= TAPER_SEGMENTS {
+            cell
+        } else {
+            cell * (THINNEST_TAIL + (1. - THINNEST_TAIL) * i as f32 / TAPER_SEGMENTS as f32)
+        }
+    };
+    let center = |square: (f32, f32)| {
+        let (x, y) = layout.square_top_left(square);
+        (x as f32 + cell / 2., y as f32 + cell / 2.)
+    };
+
+    // The body between the middles of two squares, so only the outside of a turn is rounded.
+    for (i, pair) in squares.windows(2).enumerate() {
+        if adjacent(pair[0], pair[1]) {
+            let width = thickness(i).min(thickness(i + 1));
+            frame.draw_line(center(pair[0]), center(pair[1]), width, opaque(colors[i]));
+        }
+    }
+
+    for (i, square) in squares[..body_length].iter().enumerate() {
+        let size = thickness(i);
+        let (x, y) = center(*square);
+        frame.fill_rounded_rectangle(
+            (size.round() as usize, size.round() as usize),
+            size * BODY_ROUNDING,
+            opaque(colors[i]),
+            pixel((x - size / 2., y - size / 2.)),
+        );
+    }
+
+    let (x, y) = center(head);
+    frame.fill_rounded_rectangle(
+        (layout.cell(), layout.cell()),
+        cell * HEAD_ROUNDING,
+        opaque(theme.head),
+        pixel((x - cell / 2., y - cell / 2.)),
+    );
+
+    let (forward_x, forward_y) = unit(heading(squares, fallback_direction));
+    let (side_x, side_y) = (-forward_y, forward_x);
+    for side in [-1., 1.] {
+        let eye = (
+            x + forward_x * cell * 0.18 + side * side_x * cell * 0.22,
+            y + forward_y * cell * 0.18 + side * side_y * cell * 0.22,
+        );
+        frame.fill_circle(eye, (cell * 0.11).max(1.), opaque(theme.background));
+    }
+}
+
+/// This function does a transformation from the logic to the graphics and draws the square.
+/// Can draw a square in any color or size
+pub fn draw_snake_square<T: DrawableOn>(
+    frame: &mut T,
+    color: (u8, u8, u8),
+    (square_x, square_y): (usize, usize),
+    layout: &Layout,
+) {
+    draw_square_at(frame, color, (square_x as f32, square_y as f32), layout);
+}
+
+/// Like [`draw_snake_square`], but the square can be anywhere between squares of the board.
+pub fn draw_square_at<T: DrawableOn>(
+    frame: &mut T,
+    color: (u8, u8, u8),
+    square: (f32, f32),
+    layout: &Layout,
+) {
+    frame.fill_rectangle(
+        (layout.cell(), layout.cell()),
+        (color.0, color.1, color.2),
+        layout.square_top_left(square),
+    );
+}
+
+/// The way the head looks: from the square behind the head to the head, or
+/// `fallback_direction` if the snake is too short or wraps around the edge there.
+pub fn heading(squares: &[(f32, f32)], fallback_direction: Direction) -> Direction {
+    match squares {
+        [.., neck, head] if adjacent(*neck, *head) && neck != head => {
+            let (dx, dy) = (head.0 - neck.0, head.1 - neck.1);
+            if dx.abs() >= dy.abs() {
+                if dx > 0. {
+                    Direction::Right
+                } else {
+                    Direction::Left
+                }
+            } else if dy > 0. {
+                Direction::Down
+            } else {
+                Direction::Up
+            }
+        }
+        _ => fallback_direction,
+    }
+}
+
+/// One square in `direction` on the frame, where y goes down. A snake that doesn't move yet
+/// looks right.
+fn unit(direction: Direction) -> (f32, f32) {
+    match direction {
+        Direction::Up => (0., -1.),
+        Direction::Down => (0., 1.),
+        Direction::Left => (-1., 0.),
+        Direction::Right | Direction::None => (1., 0.),
+    }
+}
+
+/// Gives [`true`] if the squares touch, and not because the snake wraps around the edge.
+fn adjacent(a: (f32, f32), b: (f32, f32)) -> bool {
+    (a.0 - b.0).abs() + (a.1 - b.1).abs() <= 1.01
+}
+
+fn opaque((red, green, blue): Rgb) -> Rgba {
+    (red, green, blue, 255)
+}
+
+fn pixel((x, y): (f32, f32)) -> (usize, usize) {
+    (x.round().max(0.) as usize, y.round().max(0.) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::recording::RecordingFrame;
+
+    #[test]
+    fn heading_from_squares() {
+        let squares = [(1., 1.), (2., 1.), (2., 2.)];
+        assert_eq!(heading(&squares, Direction::Left), Direction::Down);
+        assert_eq!(heading(&squares[..2], Direction::Left), Direction::Right);
+        assert_eq!(
+            heading(&[(1., 1.), (1., 0.4)], Direction::Left),
+            Direction::Up
+        );
+        assert_eq!(heading(&[(2., 2.)], Direction::Left), Direction::Left);
+        // Wrapping around the edge tells nothing.
+        assert_eq!(
+            heading(&[(0., 0.), (24., 0.)], Direction::Left),
+            Direction::Left
+        );
+    }
+
+    #[test]
+    fn snake_shape() {
+        let theme = Theme {
+            body: (0, 200, 0),
+            body_tail: (0, 100, 0),
+            ..Theme::default()
+        };
+        let layout = Layout::new((1000, 600), (25, 25));
+        let squares = [(1., 5.), (2., 5.), (3., 5.), (4., 5.), (5., 5.), (5., 4.)];
+        let mut frame = RecordingFrame::default();
+        draw_snake(&mut frame, &squares, Direction::None, &theme, true, &layout);
+
+        // The tail gets thinner and darker towards its end.
+        let heights: Vec<usize> = [(0, 100, 0), (0, 125, 0), (0, 150, 0), (0, 175, 0)]
+            .into_iter()
+            .map(|color| {
+                let ((_, top), (_, bottom)) = frame.bounds(color);
+                bottom - top
+            })
+            .collect();
+        assert_eq!(heights, [10, 14, 17, 20]);
+        let (head_top_left, head_bottom_right) = frame.bounds(theme.head);
+        assert_eq!(head_top_left, layout.square_top_left((5., 4.)));
+        assert_eq!(head_bottom_right, layout.square_top_left((6., 5.)));
+
+        // Both eyes are in the upper half of the head, which looks up.
+        let ((_, eyes_top), (_, eyes_bottom)) = frame.bounds(theme.background);
+        assert!(eyes_top > head_top_left.1 && eyes_bottom <= head_top_left.1 + 10);
+
+        let mut frame = RecordingFrame::default();
+        draw_snake(
+            &mut frame,
+            &squares,
+            Direction::None,
+            &theme,
+            false,
+            &layout,
+        );
+        assert!(
+            frame
+                .rectangles
+                .iter()
+                .all(|(_, color, _)| [theme.body, theme.head, theme.background].contains(color))
+        );
+
+        // Too small for details.
+        let layout = Layout::new((100, 100), (60, 60));
+        let mut frame = RecordingFrame::default();
+        draw_snake(&mut frame, &squares, Direction::None, &theme, true, &layout);
+        assert_eq!(frame.rectangles.len(), squares.len());
+        assert!(
+            frame
+                .rectangles
+                .iter()
+                .all(|&(size, _, _)| size == (layout.cell(), layout.cell()))
+        );
+    }
+}
@@ -0,0 +1,455 @@
+//! Recording and playing back games.
+//!
+//! A game is fully decided by its seed, its settings and the direction changes of the player,
+//! so a replay only stores those, each direction change tagged with the logic tick it happened in.
+//! The file is plain text:
+//!
+//! ```text
+//! snake-replay 8
+//! seed 1234
+//! difficulty Normal
+//! rules 25 25 100 4 2
+//! wall-mode Solid
+//! food-count 1
+//! ticks 57
+//! score 9
+//! input 0 Right
+//! input 12 Up
+//! ```
+//!
+//! The `rules` line holds the board width and height, the tick duration in milliseconds, the growth
+//! per food and the points multiplier of the difficulty, so a replay still plays the same when the
+//! presets change. A game with the speed ramp on also has a line
+//! `speed-ramp <start-millis or preset> <floor-millis> <step-millis>`.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::Direction;
+use crate::game::{GameOptions, SnakeGame, SpeedRamp};
+use crate::preset::Preset;
+
+/// The version written in the first line of every replay file.
+pub const REPLAY_VERSION: u32 = 8;
+
+#[derive(Debug, PartialEq, Clone)]
+/// Everything needed to play a game again tick for tick.
+pub struct Replay {
+    pub(crate) seed: u64,
+    pub(crate) difficulty: Preset,
+    pub(crate) options: GameOptions,
+    /// Every direction change with the tick it was made in.
+    pub(crate) inputs: Vec<(u64, Direction)>,
+    /// The number of logic ticks until the game was over.
+    pub(crate) ticks: u64,
+    /// The score at the end of the game.
+    pub(crate) score: usize,
+}
+
+impl Replay {
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn difficulty(&self) -> &Preset {
+        &self.difficulty
+    }
+
+    pub fn options(&self) -> GameOptions {
+        self.options
+    }
+
+    pub fn inputs(&self) -> &[(u64, Direction)] {
+        &self.inputs
+    }
+
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    pub fn score(&self) -> usize {
+        self.score
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Replay, ReplayError> {
+        std::fs::read_to_string(path)
+            .map_err(ReplayError::Io)?
+            .parse()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+
+    /// Plays the whole replay without any window or waiting and tells whether it ends with the recorded score.
+    pub fn verify(&self) -> bool {
+        let mut game = SnakeGame::from_replay(self);
+        while !game.is_over() && game.tick() < self.ticks {
+            game.step();
+        }
+        game.replay_status() == Some(ReplayStatus::Verified)
+    }
+}
+
+impl fmt::Display for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "snake-replay {REPLAY_VERSION}")?;
+        writeln!(f, "seed {}", self.seed)?;
+        let difficulty = &self.difficulty;
+        writeln!(f, "difficulty {}", difficulty.name)?;
+        writeln!(
+            f,
+            "rules {} {} {} {} {}",
+            difficulty.width,
+            difficulty.height,
+            difficulty.tick_millis,
+            difficulty.growth,
+            difficulty.points_multiplier
+        )?;
+        writeln!(f, "wall-mode {}", self.options.wall_mode)?;
+        writeln!(f, "food-count {}", self.options.food_count)?;
+        if self.options.speed_ramp {
+            let ramp = self.options.ramp;
+            let start = ramp
+                .start_millis
+                .map_or("preset".to_string(), |start| start.to_string());
+            writeln!(
+                f,
+                "speed-ramp {start} {} {}",
+                ramp.floor_millis, ramp.step_millis
+            )?;
+        }
+        writeln!(f, "ticks {}", self.ticks)?;
+        writeln!(f, "score {}", self.score)?;
+        for (tick, direction) in &self.inputs {
+            writeln!(f, "input {tick} {direction}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Replay {
+    type Err = ReplayError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut lines = text.lines().enumerate();
+
+        let version = lines
+            .next()
+            .and_then(|(_, line)| line.strip_prefix("snake-replay "))
+            .ok_or(ReplayError::NotAReplay)?;
+        let version: u32 = version
+            .trim()
+            .parse()
+            .map_err(|_| ReplayError::NotAReplay)?;
+        if version != REPLAY_VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+
+        let mut seed = None;
+        let mut difficulty = None;
+        let mut rules = None;
+        let mut wall_mode = None;
+        let mut food_count = None;
+        let mut ramp = None;
+        let mut ticks = None;
+        let mut score = None;
+        let mut inputs = Vec::new();
+
+        for (index, line) in lines {
+            let invalid = || ReplayError::InvalidLine {
+                line: index + 1,
+                content: line.to_string(),
+            };
+            let mut words = line.split_whitespace();
+            match (words.next(), words.next(), words.next()) {
+                (None, _, _) => (),
+                (Some("seed"), Some(value), None) => {
+                    seed = Some(value.parse().map_err(|_| invalid())?)
+                }
+                (Some("difficulty"), Some(value), None) => difficulty = Some(value.to_string()),
+                (Some("rules"), Some(_), Some(_)) => {
+                    let values = line
+                        .split_whitespace()
+                        .skip(1)
+                        .map(str::parse)
+                        .collect::<Result<Vec<u64>, _>>()
+                        .map_err(|_| invalid())?;
+                    let [width, height, tick_millis, growth, points_multiplier] = values[..] else {
+                        return Err(invalid());
+                    };
+                    rules = Some(Preset {
+                        width: width as usize,
+                        height: height as usize,
+                        tick_millis,
+                        growth: growth as usize,
+                        points_multiplier: points_multiplier as usize,
+                        ..Preset::default()
+                    });
+                    if !rules.as_ref().is_some_and(Preset::is_valid) {
+                        return Err(invalid());
+                    }
+                }
+                (Some("wall-mode"), Some(value), None) => {
+                    wall_mode = Some(value.parse().map_err(|_| invalid())?)
+                }
+                (Some("food-count"), Some(value), None) => {
+                    food_count = Some(value.parse().map_err(|_| invalid())?)
+                }
+                (Some("speed-ramp"), Some(start), Some(floor)) => {
+                    let (Some(step), None) = (words.next(), words.next()) else {
+                        return Err(invalid());
+                    };
+                    ramp = Some(SpeedRamp {
+                        start_millis: match start {
+                            "preset" => None,
+                            start => Some(start.parse().map_err(|_| invalid())?),
+                        },
+                        floor_millis: floor.parse().map_err(|_| invalid())?,
+                        step_millis: step.parse().map_err(|_| invalid())?,
+                    });
+                    if !ramp.as_ref().is_some_and(SpeedRamp::is_valid) {
+                        return Err(invalid());
+                    }
+                }
+                (Some("ticks"), Some(value), None) => {
+                    ticks = Some(value.parse().map_err(|_| invalid())?)
+                }
+                (Some("score"), Some(value), None) => {
+                    score = Some(value.parse().map_err(|_| invalid())?)
+                }
+                (Some("input"), Some(tick), Some(direction)) if words.next().is_none() => {
+                    let tick: u64 = tick.parse().map_err(|_| invalid())?;
+                    if inputs.last().is_some_and(|(last, _)| *last > tick) {
+                        return Err(invalid());
+                    }
+                    inputs.push((tick, direction.parse().map_err(|_| invalid())?));
+                }
+                _ => return Err(invalid()),
+            }
+        }
+
+        let difficulty = Preset {
+            name: difficulty.ok_or(ReplayError::MissingField("difficulty"))?,
+            ..rules.ok_or(ReplayError::MissingField("rules"))?
+        };
+
+        Ok(Replay {
+            seed: seed.ok_or(ReplayError::MissingField("seed"))?,
+            difficulty,
+            options: GameOptions {
+                wall_mode: wall_mode.ok_or(ReplayError::MissingField("wall-mode"))?,
+                food_count: food_count.ok_or(ReplayError::MissingField("food-count"))?,
+                speed_ramp: ramp.is_some(),
+                ramp: ramp.unwrap_or_default(),
+            },
+            inputs,
+            ticks: ticks.ok_or(ReplayError::MissingField("ticks"))?,
+            score: score.ok_or(ReplayError::MissingField("score"))?,
+        })
+    }
+}
+
+#[derive(Debug)]
+/// This enum tells why a replay could not be loaded.
+pub enum ReplayError {
+    Io(std::io::Error),
+    /// The first line is not `snake-replay <version>`.
+    NotAReplay,
+    /// The replay was written by a different version of the game, which might play it differently.
+    UnsupportedVersion(u32),
+    /// A line that could not be understood. Lines start at 1.
+    InvalidLine {
+        line: usize,
+        content: String,
+    },
+    MissingField(&'static str),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(error) => write!(f, "{error}"),
+            ReplayError::NotAReplay => write!(f, "this is not a snake replay"),
+            ReplayError::UnsupportedVersion(version) => write!(
+                f,
+                "replay version {version} is not the supported version {REPLAY_VERSION}"
+            ),
+            ReplayError::InvalidLine { line, content } => {
+                write!(f, "line {line}: cannot understand {content:?}")
+            }
+            ReplayError::MissingField(field) => write!(f, "the replay has no {field}"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+/// This enum tells how far a replay that is being played back is.
+pub enum ReplayStatus {
+    Playing,
+    /// The game is over with the recorded score.
+    Verified,
+    /// The game is over but it did not end like the recording.
+    Mismatch {
+        expected_score: usize,
+        score: usize,
+    },
+}
+
+#[derive(Debug)]
+/// The part of a [`Replay`] that a [`SnakeGame`] needs while playing it back.
+pub(crate) struct Playback {
+    inputs: VecDeque<(u64, Direction)>,
+    ticks: u64,
+    score: usize,
+}
+
+impl Playback {
+    pub(crate) fn new(replay: &Replay) -> Self {
+        Playback {
+            inputs: replay.inputs.iter().copied().collect(),
+            ticks: replay.ticks,
+            score: replay.score,
+        }
+    }
+
+    /// Gives the next direction change that was made in `tick`, if there is one left.
+    pub(crate) fn next_input(&mut self, tick: u64) -> Option<Direction> {
+        match self.inputs.front() {
+            Some((input_tick, _)) if *input_tick <= tick => {
+                self.inputs.pop_front().map(|(_, direction)| direction)
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn status(&self, is_over: bool, tick: u64, score: usize) -> ReplayStatus {
+        if !is_over {
+            ReplayStatus::Playing
+        } else if tick == self.ticks && score == self.score {
+            ReplayStatus::Verified
+        } else {
+            ReplayStatus::Mismatch {
+                expected_score: self.score,
+                score,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preset::Presets;
+
+    fn recorded_game() -> Replay {
+        let easy = Presets::builtin().get("Easy").unwrap().clone();
+        let mut game = SnakeGame::with_seed(easy, GameOptions::default(), 7);
+        let (head_x, head_y) = *game.snake().back().unwrap();
+        let (food_x, food_y) = game.food().next().unwrap().position();
+
+        // Walk to the food's column, then its row, then into the wall.
+        game.change_direction(if food_x < head_x {
+            Direction::Left
+        } else {
+            Direction::Right
+        });
+        for _ in 0..head_x.abs_diff(food_x) {
+            game.step();
+        }
+        game.change_direction(if food_y < head_y {
+            Direction::Up
+        } else {
+            Direction::Down
+        });
+        while !game.is_over() {
+            game.step();
+        }
+        game.replay()
+    }
+
+    #[test]
+    fn record_and_verify() {
+        let replay = recorded_game();
+        assert_eq!(replay.seed(), 7);
+        assert_eq!(replay.inputs().len(), 2);
+        assert!(replay.verify());
+
+        let mut wrong_score = replay.clone();
+        wrong_score.score += 1;
+        assert!(!wrong_score.verify());
+
+        let mut wrong_seed = replay.clone();
+        wrong_seed.seed += 1;
+        assert!(!wrong_seed.verify());
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let replay = recorded_game();
+        let parsed: Replay = replay.to_string().parse().unwrap();
+        assert_eq!(parsed, replay);
+        assert!(parsed.verify());
+
+        assert!(matches!(
+            "snake-replay 1\n".parse::<Replay>(),
+            Err(ReplayError::UnsupportedVersion(1))
+        ));
+        assert!(matches!(
+            "hello\n".parse::<Replay>(),
+            Err(ReplayError::NotAReplay)
+        ));
+        assert!(matches!(
+            "snake-replay 8\nseed 1\ninput 3 Sideways\n".parse::<Replay>(),
+            Err(ReplayError::InvalidLine { line: 3, .. })
+        ));
+        assert!(matches!(
+            "snake-replay 8\nseed 1\n".parse::<Replay>(),
+            Err(ReplayError::MissingField("difficulty"))
+        ));
+        assert!(matches!(
+            "snake-replay 8\nseed 1\ndifficulty Normal\n".parse::<Replay>(),
+            Err(ReplayError::MissingField("rules"))
+        ));
+        assert!(matches!(
+            "snake-replay 8\nrules 1000 10 100 4 1\n".parse::<Replay>(),
+            Err(ReplayError::InvalidLine { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn custom_round_trip() {
+        let mut replay = recorded_game();
+        replay.difficulty = Preset {
+            width: 40,
+            height: 10,
+            tick_millis: 50,
+            growth: 2,
+            ..Preset::default()
+        };
+        let text = replay.to_string();
+        assert!(text.contains("difficulty Custom\nrules 40 10 50 2 1\n"));
+        assert_eq!(text.parse::<Replay>().unwrap(), replay);
+
+        replay.options.speed_ramp = true;
+        let text = replay.to_string();
+        assert!(text.contains("speed-ramp preset 40 2\n"));
+        assert_eq!(text.parse::<Replay>().unwrap(), replay);
+
+        for ramp in [
+            "speed-ramp 0 0 2",
+            "speed-ramp preset 0 2",
+            "speed-ramp 5000 40 2",
+        ] {
+            assert!(matches!(
+                text.replace("speed-ramp preset 40 2", ramp)
+                    .parse::<Replay>(),
+                Err(ReplayError::InvalidLine { .. })
+            ));
+        }
+    }
+}
@@ -0,0 +1,95 @@
+/// Food eaten within this many ticks of the food before it keeps the streak going.
+pub const STREAK_TICKS: u64 = 30;
+/// Every this many ticks of moving give a time bonus.
+pub const TIME_BONUS_TICKS: u64 = 100;
+
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+/// The score of a game, split into where the points came from.
+pub struct Score {
+    food: usize,
+    streak_bonus: usize,
+    time_bonus: usize,
+    streak: usize,
+    best_streak: usize,
+    last_food_tick: Option<u64>,
+    ticks_moved: u64,
+}
+
+impl Score {
+    /// All the points together.
+    pub fn total(&self) -> usize {
+        self.food + self.streak_bonus + self.time_bonus
+    }
+
+    /// The points of the food itself.
+    pub fn food(&self) -> usize {
+        self.food
+    }
+
+    /// The extra points for eating food quickly one after another.
+    pub fn streak_bonus(&self) -> usize {
+        self.streak_bonus
+    }
+
+    /// The extra points for staying alive.
+    pub fn time_bonus(&self) -> usize {
+        self.time_bonus
+    }
+
+    /// How many food items were eaten quickly one after another, counting the last one.
+    pub fn streak(&self) -> usize {
+        self.streak
+    }
+
+    pub fn best_streak(&self) -> usize {
+        self.best_streak
+    }
+
+    /// Adds `points` for food eaten in `tick`. Every food of a streak after the first one
+    /// also gives `multiplier` points for every food before it in the streak.
+    pub(crate) fn food_eaten(&mut self, points: usize, multiplier: usize, tick: u64) {
+        let in_streak = self
+            .last_food_tick
+            .is_some_and(|last| tick - last <= STREAK_TICKS);
+        self.streak = if in_streak { self.streak + 1 } else { 1 };
+        self.best_streak = self.best_streak.max(self.streak);
+        self.last_food_tick = Some(tick);
+        self.food += points * multiplier;
+        self.streak_bonus += (self.streak - 1) * multiplier;
+    }
+
+    /// Counts a tick in which the snake moved. Every [`TIME_BONUS_TICKS`] of them give `multiplier` points.
+    pub(crate) fn moved(&mut self, multiplier: usize) {
+        self.ticks_moved += 1;
+        if self.ticks_moved.is_multiple_of(TIME_BONUS_TICKS) {
+            self.time_bonus += multiplier;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn breakdown() {
+        let mut score = Score::default();
+        score.food_eaten(1, 2, 10);
+        score.food_eaten(1, 2, 10 + STREAK_TICKS);
+        score.food_eaten(5, 2, 20 + STREAK_TICKS);
+        assert_eq!(score.food(), 14);
+        assert_eq!(score.streak(), 3);
+        assert_eq!(score.streak_bonus(), 2 + 4);
+
+        score.food_eaten(1, 2, 21 + 2 * STREAK_TICKS);
+        assert_eq!(score.streak(), 1);
+        assert_eq!(score.best_streak(), 3);
+        assert_eq!(score.streak_bonus(), 6);
+
+        for _ in 0..TIME_BONUS_TICKS * 2 + 1 {
+            score.moved(2);
+        }
+        assert_eq!(score.time_bonus(), 4);
+        assert_eq!(score.total(), 16 + 6 + 4);
+    }
+}
@@ -1,17 +1,30 @@
-use std::collections::VecDeque;
+use std::collections::{BTreeSet, VecDeque};
 
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
-use crate::{Direction, internal::GameResult};
+use crate::{
+    Direction, WallMode,
+    food::{Food, FoodKind, SHRINK_AMOUNT},
+    internal::{DeathCause, GameEvent, GameResult},
+    level::Level,
+    occupancy::{Occupancy, Square},
+};
 
-const DEFAULT_GROWTH: usize = 4;
+/// **How many squares the snake grows for every food it eats, unless it is changed.**
+pub const DEFAULT_GROWTH: usize = 4;
+/// How many direction changes can wait for a later step. Anything more is dropped.
+const MAX_QUEUED_DIRECTIONS: usize = 2;
 
 #[derive(Debug, PartialEq, Clone)]
 /// The overall Snake Game State.
 pub struct SnakeLogic {
     /// **This is a vector showing all the squares where the snake is.**
     position_snake: VecDeque<(usize, usize)>,
-    position_food: (usize, usize),
+    /// **All the food on the board. Every one is respawned on its own when it is eaten or disappears.**
+    food: Vec<Food>,
+    /// **How many food items should be on the board at once.**
+    food_count: usize,
     direction: Direction,
     height: usize,
     width: usize,
@@ -21,6 +34,24 @@ pub struct SnakeLogic {
     /// **A boolean that indicates wether you can change direction or not.
     /// After you change direction, it is false. Default is true**
     can_change_direction: bool,
+    /// **Direction changes that came in after the direction was already changed in this step.
+    /// Every step takes the first one.**
+    queued_directions: VecDeque<Direction>,
+    /// What happens when the snake leaves the board.
+    wall_mode: WallMode,
+    /// **The blocked squares of the arena. Running into one of them is game over.**
+    obstacles: BTreeSet<(usize, usize)>,
+    /// **The only squares where food may appear. If it is empty, food can appear anywhere.**
+    food_spawns: Vec<(usize, usize)>,
+    /// **The seed the random number generator started with.**
+    seed: u64,
+    /// **Every random decision (snake start, food) comes from here, so the same seed gives the same game.**
+    rng: ChaCha8Rng,
+    /// **What happened in the steps since the last call to [`SnakeLogic::take_events`].**
+    events: Vec<GameEvent>,
+    /// **What is on every square, kept up to date with the snake and the obstacles,
+    /// so collisions and food placement do not depend on the length of the snake.**
+    occupancy: Occupancy,
 }
 
 impl SnakeLogic {
@@ -31,6 +62,24 @@ impl SnakeLogic {
     /// **This function creates a new instance with the given height and width.
     /// It will return [`None`] if the width or height are out of a specific boundary (MIN or MAX height or width).**
     pub fn new(width: usize, height: usize) -> Option<Self> {
+        Self::with_seed(width, height, rand::random())
+    }
+
+    /// **This function creates a new instance with the given height and width.
+    /// Two instances created with the same seed place the snake and every food at the same squares.**
+    pub fn with_seed(width: usize, height: usize, seed: u64) -> Option<Self> {
+        Self::with_obstacles(width, height, BTreeSet::new(), seed)
+    }
+
+    /// **This function creates a new instance with the given height, width, obstacles and seed.
+    /// It will return [`None`] if the width or height are out of a specific boundary (MIN or MAX height or width),
+    /// if an obstacle is outside of the board or if there is no free square left for the snake and the food.**
+    pub fn with_obstacles(
+        width: usize,
+        height: usize,
+        obstacles: BTreeSet<(usize, usize)>,
+        seed: u64,
+    ) -> Option<Self> {
         if width < Self::MIN_WIDTH
             || height < Self::MIN_HEIGHT
             || width > Self::MAX_WIDTH
@@ -38,89 +87,112 @@ impl SnakeLogic {
         {
             return None;
         }
-        let position_snake = vec![Self::generate_initial_snake(width, height)].into();
-        let position_food = Self::generate_food_inner(width, height, &position_snake);
+        if obstacles.iter().any(|&(x, y)| x >= width || y >= height)
+            || obstacles.len() + 2 > width * height
+        {
+            return None;
+        }
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut occupancy = Occupancy::new(width, height);
+        for obstacle in &obstacles {
+            occupancy.set(*obstacle, Square::Obstacle);
+        }
+        let head = Self::generate_initial_snake(&mut rng, &occupancy);
+        occupancy.set(head, Square::Snake);
+        let food = occupancy
+            .random_free(&mut rng)
+            .expect("There are at least two free squares");
+        occupancy.set(food, Square::Food);
         Some(SnakeLogic {
-            position_snake,
+            position_snake: vec![head].into(),
             direction: Direction::None,
-            position_food,
+            food: vec![Food::new(food, FoodKind::Normal)],
+            food_count: 1,
             height,
             width,
             amount_of_growth: 0,
             growth_per_food: DEFAULT_GROWTH,
             can_change_direction: true,
+            queued_directions: VecDeque::new(),
+            wall_mode: WallMode::default(),
+            obstacles,
+            food_spawns: Vec::new(),
+            seed,
+            rng,
+            events: Vec::new(),
+            occupancy,
         })
     }
+
+    /// **This function creates a new instance from a [`Level`].
+    /// The snake starts where the level says and food only appears on the level's food spawns, if it has any.**
+    pub fn from_level(level: &Level, seed: u64) -> Self {
+        let mut logic = Self::with_obstacles(
+            level.width(),
+            level.height(),
+            level.obstacles().clone(),
+            seed,
+        )
+        .expect("Levels are validated when they are parsed");
+        logic.set_snake(vec![level.start()].into());
+        logic.direction = level.start_direction();
+        logic.food_spawns = level.food_spawns().to_vec();
+        logic.set_food(Vec::<Food>::new());
+        logic
+    }
     /// **This function changes [`self`] s direction.
-    /// It won't change the direction if you let it change to the opposite direction or if you let it change to the same direction it already has.**
+    /// It won't change the direction if you let it change to the opposite direction or if you let it change to the same direction it already has.
+    /// If the direction was already changed since the last step, the new direction is queued and used in a later step,
+    /// so it is compared with the queued direction instead of the current one.**
     pub fn change_direction(&mut self, direction: Direction) {
-        if !self.can_change_direction {
+        let current = self
+            .queued_directions
+            .back()
+            .copied()
+            .unwrap_or(self.direction);
+
+        let allowed = match current {
+            Direction::Up | Direction::Down => {
+                matches!(direction, Direction::Left | Direction::Right)
+            }
+            Direction::Left | Direction::Right => {
+                matches!(direction, Direction::Up | Direction::Down)
+            }
+            Direction::None => direction != Direction::None,
+        };
+        if !allowed {
             return;
         }
 
-        match self.direction {
-            Direction::Up => match direction {
-                Direction::Up => return,
-                Direction::Down => return,
-                Direction::Left => self.direction = Direction::Left,
-
-                Direction::Right => self.direction = Direction::Right,
-
-                Direction::None => return,
-            },
-            Direction::Down => match direction {
-                Direction::Up => return,
-                Direction::Down => return,
-                Direction::Left => self.direction = Direction::Left,
-
-                Direction::Right => self.direction = Direction::Right,
-
-                Direction::None => return,
-            },
-            Direction::Left => match direction {
-                Direction::Up => self.direction = Direction::Up,
-
-                Direction::Down => self.direction = Direction::Down,
-
-                Direction::Left => return,
-                Direction::Right => return,
-                Direction::None => return,
-            },
-            Direction::Right => match direction {
-                Direction::Up => self.direction = Direction::Up,
-
-                Direction::Down => self.direction = Direction::Down,
-
-                Direction::Left => return,
-                Direction::Right => return,
-                Direction::None => return,
-            },
-            Direction::None => match direction {
-                Direction::Up => self.direction = Direction::Up,
-
-                Direction::Down => self.direction = Direction::Down,
-
-                Direction::Left => self.direction = Direction::Left,
-
-                Direction::Right => self.direction = Direction::Right,
-
-                Direction::None => return,
-            },
+        if self.can_change_direction {
+            self.direction = direction;
+            self.can_change_direction = false;
+        } else if self.queued_directions.len() < MAX_QUEUED_DIRECTIONS {
+            self.queued_directions.push_back(direction);
         }
-        self.can_change_direction = false;
     }
 
     fn new_head(&self) -> Option<(usize, usize)> {
         let head = *self.position_snake.back().unwrap();
-        let new_head = match self.direction {
-            Direction::Up => (head.0, head.1.checked_sub(1)?),
-            Direction::Down => (head.0, head.1 + 1),
-            Direction::Left => (head.0.checked_sub(1)?, head.1),
-            Direction::Right => (head.0 + 1, head.1),
-            Direction::None => head,
+        let (width, height) = (self.width(), self.height());
+        let new_head = match self.wall_mode {
+            WallMode::Solid => match self.direction {
+                Direction::Up => (head.0, head.1.checked_sub(1)?),
+                Direction::Down => (head.0, head.1 + 1),
+                Direction::Left => (head.0.checked_sub(1)?, head.1),
+                Direction::Right => (head.0 + 1, head.1),
+                Direction::None => head,
+            },
+            WallMode::WrapAround => match self.direction {
+                Direction::Up => (head.0, (head.1 + height - 1) % height),
+                Direction::Down => (head.0, (head.1 + 1) % height),
+                Direction::Left => ((head.0 + width - 1) % width, head.1),
+                Direction::Right => ((head.0 + 1) % width, head.1),
+                Direction::None => head,
+            },
         };
 
-        if new_head.0 >= self.width() || new_head.1 >= self.height() {
+        if new_head.0 >= width || new_head.1 >= height {
             return None;
         }
 
@@ -128,38 +200,182 @@ impl SnakeLogic {
     }
 
     fn abstract_next_step(&mut self) -> GameResult {
+        if self.direction == Direction::None {
+            return GameResult::NoOp;
+        }
+        let head = *self.position_snake.back().unwrap();
         let new_head = match self.new_head() {
             Some(h) => h,
-            None => return GameResult::GameOver,
+            None => return self.die(DeathCause::Wall, head),
         };
-        if self.snake().contains(&new_head) && self.direction != Direction::None {
-            return GameResult::GameOver;
-        }
+        let ate_food = match self.occupancy.get(new_head) {
+            Some(Square::Snake) => return self.die(DeathCause::Snake, new_head),
+            Some(Square::Obstacle) => return self.die(DeathCause::Obstacle, new_head),
+            Some(Square::Food) => true,
+            Some(Square::Free(_)) | None => false,
+        };
+        let was_growing = self.amount_of_growth > 0;
         self.position_snake.push_back(new_head);
+        self.occupancy.set(new_head, Square::Snake);
         if self.amount_of_growth == 0 {
-            self.position_snake.pop_front();
+            if let Some(tail) = self.position_snake.pop_front() {
+                self.occupancy.free(tail);
+            }
         } else {
             self.amount_of_growth -= 1;
+            self.events.push(GameEvent::Grew {
+                length: self.position_snake.len(),
+            });
         }
+        self.events.push(GameEvent::Moved { head: new_head });
 
-        if new_head == self.food() {
-            self.amount_of_growth += self.growth_per_food;
-            self.position_food = self.generate_food();
+        if ate_food && let Some(index) = self.food.iter().position(|f| f.position() == new_head) {
+            let kind = self.food.remove(index).kind();
+            if kind == FoodKind::Shrink {
+                self.shrink();
+            } else {
+                self.amount_of_growth += self.growth_per_food;
+            }
+            self.events.push(GameEvent::FoodEaten {
+                position: new_head,
+                kind,
+                new_length: self.position_snake.len(),
+            });
+        }
+        self.expire_food();
+        self.fill_food();
+        if self.food.is_empty() && self.occupancy.free_squares().is_empty() {
+            self.events.push(GameEvent::BoardCleared {
+                length: self.position_snake.len(),
+            });
+            return GameResult::BoardCleared;
+        }
+        if was_growing && self.amount_of_growth == 0 {
+            self.events.push(GameEvent::GrowthFinished {
+                length: self.position_snake.len(),
+            });
         }
         GameResult::NoOp
     }
+
+    /// **This removes [`SHRINK_AMOUNT`] squares from the tail, but never the head.**
+    fn shrink(&mut self) {
+        let amount = SHRINK_AMOUNT.min(self.position_snake.len() - 1);
+        for tail in self.position_snake.drain(..amount) {
+            self.occupancy.free(tail);
+        }
+        self.events.push(GameEvent::Shrank {
+            length: self.position_snake.len(),
+        });
+    }
+
+    /// **This counts down the food that disappears after some ticks and removes the food whose time is up.**
+    fn expire_food(&mut self) {
+        let mut index = 0;
+        while index < self.food.len() {
+            if self.food[index].tick() {
+                let food = self.food.remove(index);
+                self.occupancy.free(food.position());
+                self.events.push(GameEvent::FoodExpired {
+                    position: food.position(),
+                    kind: food.kind(),
+                });
+            } else {
+                index += 1;
+            }
+        }
+    }
+
+    fn die(&mut self, cause: DeathCause, cell: (usize, usize)) -> GameResult {
+        self.events.push(GameEvent::Died { cause, cell });
+        GameResult::GameOver
+    }
+
     /// **This function moves the snake by the number of steps in the args based on the direction of** [`self`] **and returns wether the game is over or not.
     /// It also alters** [`self`] **s snake position and may alter food position.**
     pub fn next_step(&mut self) -> GameResult {
+        let result = self.abstract_next_step();
         self.can_change_direction = true;
-        self.abstract_next_step()
+        if let Some(direction) = self.queued_directions.pop_front() {
+            self.direction = direction;
+            self.can_change_direction = false;
+        }
+        result
+    }
+
+    /// **This function gives everything that happened since it was called last, oldest first.**
+    pub fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
     }
 
     pub fn snake(&self) -> &VecDeque<(usize, usize)> {
         &self.position_snake
     }
-    pub fn food(&self) -> (usize, usize) {
-        self.position_food
+
+    /// **This function puts the snake on the given squares, the tail first and the head last.
+    /// Food that ends up under the snake is moved to a free square.**
+    pub fn set_snake(&mut self, snake: VecDeque<(usize, usize)>) {
+        for square in &self.position_snake {
+            self.occupancy.free(*square);
+        }
+        self.food.retain(|food| !snake.contains(&food.position()));
+        for square in &snake {
+            self.occupancy.set(*square, Square::Snake);
+        }
+        self.position_snake = snake;
+        self.fill_food();
+    }
+
+    /// **This function puts the given food on the board. Squares can be given instead of [`Food`] for normal food.
+    /// Food on squares that are not free is left out.
+    /// If there is less food than [`SnakeLogic::food_count`], the rest is put on random free squares.**
+    pub fn set_food<F: Into<Food>>(&mut self, food: Vec<F>) {
+        for old in self.food.drain(..) {
+            self.occupancy.free(old.position());
+        }
+        for new in food {
+            let new = new.into();
+            if self.occupancy.is_free(new.position()) {
+                self.occupancy.set(new.position(), Square::Food);
+                self.food.push(new);
+            }
+        }
+        self.fill_food();
+    }
+
+    /// **All the food on the board.**
+    pub fn food(&self) -> &[Food] {
+        &self.food
+    }
+
+    /// **How many food items are on the board at once, as long as there is room for them.**
+    pub fn food_count(&self) -> usize {
+        self.food_count
+    }
+
+    /// **This function changes how many food items are on the board at once. It is at least 1.**
+    pub fn set_food_count(&mut self, food_count: usize) {
+        self.food_count = food_count.max(1);
+        while self.food.len() > self.food_count {
+            if let Some(old) = self.food.pop() {
+                self.occupancy.free(old.position());
+            }
+        }
+        self.fill_food();
+    }
+
+    /// **How many squares the snake grows for every food it eats.**
+    pub fn growth_per_food(&self) -> usize {
+        self.growth_per_food
+    }
+
+    /// **This function changes how many squares the snake grows for every food it eats.**
+    pub fn set_growth_per_food(&mut self, growth_per_food: usize) {
+        self.growth_per_food = growth_per_food;
+    }
+
+    pub fn obstacles(&self) -> &BTreeSet<(usize, usize)> {
+        &self.obstacles
     }
 
     pub fn height(&self) -> usize {
@@ -175,41 +391,62 @@ impl SnakeLogic {
         self.direction
     }
 
-    fn random_square(width: usize, height: usize) -> (usize, usize) {
-        let square_x = rand::rng().random_range(0..width) as usize;
-        let square_y = rand::rng().random_range(0..height) as usize;
-        (square_x, square_y)
+    pub fn wall_mode(&self) -> WallMode {
+        self.wall_mode
     }
 
-    /// **This generates the snake in a random place.**
-    fn generate_initial_snake(width: usize, height: usize) -> (usize, usize) {
-        Self::random_square(width, height)
+    /// **This function changes what happens when the snake reaches the edge of the board.**
+    pub fn set_wall_mode(&mut self, wall_mode: WallMode) {
+        self.wall_mode = wall_mode;
     }
 
-    /// **This generates the food in a random place.**
-    fn generate_food(&self) -> (usize, usize) {
-        Self::generate_food_inner(self.width, self.height, self.snake())
+    pub fn seed(&self) -> u64 {
+        self.seed
     }
 
-    /// **This generates the food in a random place.**
-    fn generate_food_inner(
-        width: usize,
-        height: usize,
-        snake: &VecDeque<(usize, usize)>,
-    ) -> (usize, usize) {
-        loop {
-            let (food_x, food_y) = Self::random_square(width, height);
-            if snake.contains(&(food_x, food_y)) {
-                continue;
-            } else {
-                return (food_x, food_y);
-            }
+    /// **This generates the snake in a random place that is not an obstacle.**
+    fn generate_initial_snake(rng: &mut ChaCha8Rng, occupancy: &Occupancy) -> (usize, usize) {
+        occupancy
+            .random_free(rng)
+            .expect("There are at least two free squares")
+    }
+
+    /// **This puts food on free squares until there are [`SnakeLogic::food_count`] food items or no free square is left.**
+    /// **The kind of every new food item is random too.**
+    fn fill_food(&mut self) {
+        while self.food.len() < self.food_count
+            && let Some(square) = self.generate_food()
+        {
+            self.occupancy.set(square, Square::Food);
+            let kind = FoodKind::random(&mut self.rng);
+            self.food.push(Food::new(square, kind));
         }
     }
+
+    /// **This generates the food in a random free place. Every free square is equally likely.
+    /// If there are food spawns, it picks one of the spawns that is free and never any other square.
+    /// It gives [`None`] if there is no free square (or free spawn) left.**
+    fn generate_food(&mut self) -> Option<(usize, usize)> {
+        if self.food_spawns.is_empty() {
+            return self.occupancy.random_free(&mut self.rng);
+        }
+        let free_spawns: Vec<(usize, usize)> = self
+            .food_spawns
+            .iter()
+            .filter(|spawn| self.occupancy.is_free(**spawn))
+            .copied()
+            .collect();
+        if free_spawns.is_empty() {
+            return None;
+        }
+        Some(free_spawns[self.rng.random_range(0..free_spawns.len())])
+    }
 }
+
 #[cfg(test)]
 mod tests {
-    use crate::Direction;
+    use crate::food::BONUS_FOOD_TICKS;
+    use crate::{Direction, WallMode};
 
     use super::*;
 
@@ -219,24 +456,24 @@ mod tests {
             let mut logic = SnakeLogic::new(25, 25).unwrap();
             logic.growth_per_food = 2;
             logic.direction = Direction::Right;
-            logic.position_snake = vec![(0, 0)].into();
-            logic.position_food = (24, 24);
+            logic.set_snake(vec![(0, 0)].into());
+            logic.set_food(vec![(24, 24)]);
             assert!(!logic.next_step().is_over());
             assert_eq!(*logic.snake(), [(1usize, 0usize)]);
-            logic.position_snake = vec![(0, 0)].into();
-            logic.position_food = (1, 0);
+            logic.set_snake(vec![(0, 0)].into());
+            logic.set_food(vec![(1, 0)]);
             assert!(!logic.next_step().is_over());
             assert_eq!(*logic.snake(), [(1usize, 0usize)]);
             assert!(!logic.next_step().is_over());
             assert_eq!(*logic.snake(), [(1, 0), (2, 0)]);
             assert!(!logic.next_step().is_over());
             assert_eq!(*logic.snake(), [(1, 0), (2, 0), (3, 0)]);
-            logic.position_food = (24, 24);
-            logic.position_snake = [(1, 0), (2, 0), (3, 0)].into();
+            logic.set_food(vec![(24, 24)]);
+            logic.set_snake([(1, 0), (2, 0), (3, 0)].into());
             let mut logic = SnakeLogic::new(25, 25).unwrap();
             logic.growth_per_food = 2;
             logic.direction = Direction::Right;
-            logic.position_snake = [(1, 0), (2, 0), (3, 0)].into();
+            logic.set_snake([(1, 0), (2, 0), (3, 0)].into());
             assert!(!logic.next_step().is_over());
             assert_eq!(*logic.snake(), [(2, 0), (3, 0), (4, 0)])
         }
@@ -245,12 +482,12 @@ mod tests {
             logic.growth_per_food = 2;
 
             logic.direction = Direction::Left;
-            logic.position_snake = vec![(24, 24)].into();
-            logic.position_food = (23, 24);
+            logic.set_snake(vec![(24, 24)].into());
+            logic.set_food(vec![(23, 24)]);
             assert!(!logic.next_step().is_over());
             assert_eq!(*logic.snake(), [(23, 24)]);
-            logic.position_snake = vec![(24, 24)].into();
-            logic.position_food = (0, 0);
+            logic.set_snake(vec![(24, 24)].into());
+            logic.set_food(vec![(0, 0)]);
             assert!(!logic.next_step().is_over());
             assert_eq!(*logic.snake(), [(24, 24), (23, 24)]);
 
@@ -270,20 +507,20 @@ mod tests {
                 logic.growth_per_food = 2;
 
                 logic.direction = Direction::Down;
-                logic.position_snake = vec![(0, 0)].into();
+                logic.set_snake(vec![(0, 0)].into());
                 assert!(!logic.next_step().is_over());
                 assert_eq!(*logic.snake(), [(0, 1)]);
             }
             let mut logic = SnakeLogic::new(25, 25).unwrap();
-            logic.position_food = (24, 24);
+            logic.set_food(vec![(24, 24)]);
             logic.direction = Direction::Down;
-            logic.position_snake = vec![(0, 0)].into();
+            logic.set_snake(vec![(0, 0)].into());
             assert!(!logic.next_step().is_over());
             assert_eq!(*logic.snake(), [(0usize, 1usize)]);
             assert!(!logic.next_step().is_over());
 
             assert_eq!(*logic.snake(), [(0, 2)]);
-            logic.position_food = (0, 3);
+            logic.set_food(vec![(0, 3)]);
             assert!(!logic.next_step().is_over());
             assert_eq!(*logic.snake(), [(0, 3)]);
 
@@ -298,30 +535,30 @@ mod tests {
         logic.growth_per_food = 2;
 
         logic.direction = Direction::Up;
-        logic.position_snake = vec![(24, 24)].into();
-        logic.position_food = (0, 0);
+        logic.set_snake(vec![(24, 24)].into());
+        logic.set_food(vec![(0, 0)]);
 
         assert!(!logic.next_step().is_over());
         assert_eq!(*logic.snake(), [(24, 23)]);
-        logic.position_snake = vec![(24, 24)].into();
-        logic.position_food = (0, 0);
+        logic.set_snake(vec![(24, 24)].into());
+        logic.set_food(vec![(0, 0)]);
 
         assert!(!logic.next_step().is_over());
         assert_eq!(*logic.snake(), [(24, 23)]);
-        logic.position_food = (24, 22);
+        logic.set_food(vec![(24, 22)]);
 
         assert!(!logic.next_step().is_over());
         assert_eq!(*logic.snake(), [(24, 22)]);
-        logic.position_food = (0, 0);
+        logic.set_food(vec![(0, 0)]);
 
         assert!(!logic.next_step().is_over());
         assert_eq!(*logic.snake(), [(24, 22), (24, 21)]);
 
-        logic.position_food = (0, 0);
+        logic.set_food(vec![(0, 0)]);
 
         assert!(!logic.next_step().is_over());
         assert_eq!(*logic.snake(), [(24, 22), (24, 21), (24, 20)]);
-        logic.position_food = (0, 0);
+        logic.set_food(vec![(0, 0)]);
 
         assert!(!logic.next_step().is_over());
         assert_eq!(*logic.snake(), [(24, 21), (24, 20), (24, 19)])
@@ -332,8 +569,8 @@ mod tests {
         {
             let mut logic = SnakeLogic::new(10, 10).unwrap();
             logic.growth_per_food = 1;
-            logic.position_snake = vec![(2, 0), (1, 0), (0, 0), (0, 1)].into();
-            logic.position_food = (1, 1);
+            logic.set_snake(vec![(2, 0), (1, 0), (0, 0), (0, 1)].into());
+            logic.set_food(vec![(1, 1)]);
             logic.direction = Direction::Right;
             assert_eq!(logic.next_step(), GameResult::NoOp);
             assert_eq!(logic.direction(), Direction::Right);
@@ -342,7 +579,7 @@ mod tests {
         }
         {
             let mut logic = SnakeLogic::new(10, 10).unwrap();
-            logic.position_snake = vec![(2, 3), (2, 2), (2, 1), (2, 0)].into();
+            logic.set_snake(vec![(2, 3), (2, 2), (2, 1), (2, 0)].into());
             logic.growth_per_food = 1;
 
             assert_eq!(logic.height(), 10);
@@ -360,25 +597,25 @@ mod tests {
         {
             // Initial snake state.
             let mut logic = SnakeLogic::new(10, 10).unwrap();
-            logic.position_snake = vec![(2, 2), (2, 1), (2, 0), (3, 0)].into();
+            logic.set_snake(vec![(2, 2), (2, 1), (2, 0), (3, 0)].into());
             logic.growth_per_food = 1;
 
             assert_eq!(*logic.snake(), vec![(2, 2), (2, 1), (2, 0), (3, 0)]);
 
             // Snake hits wall.
-            logic.position_snake = vec![(1, 3), (1, 2), (1, 1), (10, 10)].into();
+            logic.set_snake(vec![(1, 3), (1, 2), (1, 1), (10, 10)].into());
             logic.direction = Direction::Right;
 
             assert!(logic.next_step().is_over());
 
             // Snake hits self.
-            logic.position_snake = vec![(2, 3), (2, 2), (1, 2), (1, 3)].into();
+            logic.set_snake(vec![(2, 3), (2, 2), (1, 2), (1, 3)].into());
             logic.direction = Direction::Right;
 
             assert!(logic.next_step().is_over());
 
             // Normal.
-            logic.position_snake = vec![(2, 0), (2, 1), (3, 1), (3, 0)].into();
+            logic.set_snake(vec![(2, 0), (2, 1), (3, 1), (3, 0)].into());
             logic.direction = Direction::Right;
             assert!(!logic.next_step().is_over())
         }
@@ -388,8 +625,8 @@ mod tests {
     fn next_step_up() {
         {
             let mut logic = SnakeLogic::new(10, 10).unwrap();
-            logic.position_snake = vec![(0, 2), (1, 2), (2, 2), (2, 1)].into();
-            logic.position_food = (2, 0);
+            logic.set_snake(vec![(0, 2), (1, 2), (2, 2), (2, 1)].into());
+            logic.set_food(vec![(2, 0)]);
             logic.growth_per_food = 1;
 
             logic.direction = Direction::Up;
@@ -400,36 +637,36 @@ mod tests {
         }
         {
             let mut logic = SnakeLogic::new(10, 10).unwrap();
-            logic.position_snake = vec![(2, 3), (2, 2), (2, 1), (2, 0)].into();
+            logic.set_snake(vec![(2, 3), (2, 2), (2, 1), (2, 0)].into());
             logic.growth_per_food = 1;
 
             assert_eq!(logic.height(), 10);
             assert_eq!(logic.width(), 10);
             assert_eq!(logic.direction(), Direction::None);
             logic.direction = Direction::Up;
-            logic.position_snake = vec![(0, 0)].into();
+            logic.set_snake(vec![(0, 0)].into());
             let next = logic.next_step();
             assert!(next.is_over());
         }
         {
             // Initial state.
             let mut logic = SnakeLogic::new(10, 10).unwrap();
-            logic.position_snake = vec![(2, 2), (2, 1), (2, 0), (3, 0)].into();
+            logic.set_snake(vec![(2, 2), (2, 1), (2, 0), (3, 0)].into());
             logic.growth_per_food = 1;
 
             assert_eq!(*logic.snake(), vec![(2, 2), (2, 1), (2, 0), (3, 0)]);
             // snake hits wall
-            logic.position_snake = vec![(1, 3), (1, 2), (1, 1), (1, 0)].into();
+            logic.set_snake(vec![(1, 3), (1, 2), (1, 1), (1, 0)].into());
             logic.direction = Direction::Up;
 
             assert!(logic.next_step().is_over());
             // snake hits self.
-            logic.position_snake = vec![(2, 3), (2, 2), (1, 2), (1, 3)].into();
+            logic.set_snake(vec![(2, 3), (2, 2), (1, 2), (1, 3)].into());
             logic.direction = Direction::Up;
 
             assert!(logic.next_step().is_over());
             // Normal
-            logic.position_snake = vec![(0, 2), (1, 2), (2, 2), (2, 1)].into();
+            logic.set_snake(vec![(0, 2), (1, 2), (2, 2), (2, 1)].into());
             logic.direction = Direction::Up;
 
             assert!(!logic.next_step().is_over());
@@ -440,16 +677,16 @@ mod tests {
     fn next_step_down() {
         {
             let mut logic = SnakeLogic::new(25, 25).unwrap();
-            logic.position_snake = vec![(2, 0), (1, 0), (0, 0), (0, 1)].into();
+            logic.set_snake(vec![(2, 0), (1, 0), (0, 0), (0, 1)].into());
             logic.growth_per_food = 1;
 
             logic.direction = Direction::Down;
-            logic.position_food = (4, 1);
+            logic.set_food(vec![(4, 1)]);
             assert_eq!(logic.next_step(), GameResult::NoOp);
             assert_eq!(logic.direction(), Direction::Down);
             assert_eq!(*logic.snake(), [(1, 0), (0, 0), (0, 1), (0, 2)]);
-            logic.position_food = (4, 4);
-            logic.position_snake = [(0, 0), (0, 1), (0, 2), (0, 3)].into();
+            logic.set_food(vec![(4, 4)]);
+            logic.set_snake([(0, 0), (0, 1), (0, 2), (0, 3)].into());
             assert!(!logic.next_step().is_over());
             assert_eq!(*logic.snake(), [(0, 1), (0, 2), (0, 3), (0, 4)])
         }
@@ -457,7 +694,7 @@ mod tests {
             let mut logic = SnakeLogic::new(5, 5).unwrap();
             logic.growth_per_food = 1;
 
-            logic.position_snake = vec![(2, 3), (2, 2), (2, 1), (2, 0)].into();
+            logic.set_snake(vec![(2, 3), (2, 2), (2, 1), (2, 0)].into());
 
             assert_eq!(logic.height(), 5);
             assert_eq!(logic.width(), 5);
@@ -472,23 +709,23 @@ mod tests {
             let mut logic = SnakeLogic::new(5, 5).unwrap();
             logic.growth_per_food = 1;
 
-            logic.position_snake = vec![(2, 2), (2, 1), (2, 0), (3, 0)].into();
+            logic.set_snake(vec![(2, 2), (2, 1), (2, 0), (3, 0)].into());
             assert_eq!(*logic.snake(), vec![(2, 2), (2, 1), (2, 0), (3, 0)]);
 
             // Snake hits wall.
-            logic.position_snake = vec![(2, 5), (3, 5), (4, 5), (5, 5)].into();
+            logic.set_snake(vec![(2, 5), (3, 5), (4, 5), (5, 5)].into());
             logic.direction = Direction::Down;
 
             assert!(logic.next_step().is_over());
 
             // Snake hits self.
-            logic.position_snake = vec![(1, 4), (2, 4), (2, 3), (1, 3)].into();
+            logic.set_snake(vec![(1, 4), (2, 4), (2, 3), (1, 3)].into());
             logic.direction = Direction::Down;
 
             assert!(logic.next_step().is_over());
 
             // Normal
-            logic.position_snake = vec![(4, 0), (3, 0), (2, 0), (1, 0)].into();
+            logic.set_snake(vec![(4, 0), (3, 0), (2, 0), (1, 0)].into());
             logic.direction = Direction::Down;
 
             assert!(!logic.next_step().is_over());
@@ -501,14 +738,14 @@ mod tests {
             let mut logic = SnakeLogic::new(5, 5).unwrap();
             logic.growth_per_food = 1;
 
-            logic.position_snake = vec![(3, 3), (3, 2), (3, 1), (2, 1)].into();
+            logic.set_snake(vec![(3, 3), (3, 2), (3, 1), (2, 1)].into());
             logic.direction = Direction::Left;
-            logic.position_food = (1, 1);
+            logic.set_food(vec![(1, 1)]);
             assert_eq!(logic.next_step(), GameResult::NoOp);
             assert_eq!(logic.direction(), Direction::Left);
             let x: VecDeque<(usize, usize)> = vec![(3, 2), (3, 1), (2, 1), (1, 1)].into();
             assert_eq!(*logic.snake(), x);
-            logic.position_food = (4, 3);
+            logic.set_food(vec![(4, 3)]);
             logic.direction = Direction::Left;
             assert!(!logic.next_step().is_over());
             assert_eq!(*logic.snake(), vec![(3, 2), (3, 1), (2, 1), (1, 1), (0, 1)])
@@ -517,7 +754,7 @@ mod tests {
             let mut logic = SnakeLogic::new(5, 5).unwrap();
             logic.growth_per_food = 1;
 
-            logic.position_snake = vec![(3, 0), (2, 0), (1, 0), (0, 0)].into();
+            logic.set_snake(vec![(3, 0), (2, 0), (1, 0), (0, 0)].into());
 
             assert_eq!(logic.height(), 5);
             assert_eq!(logic.width(), 5);
@@ -531,23 +768,23 @@ mod tests {
             // Initial snake state.
             let mut logic = SnakeLogic::new(5, 5).unwrap();
             logic.growth_per_food = 1;
-            logic.position_snake = vec![(2, 2), (2, 1), (2, 0), (3, 0)].into();
+            logic.set_snake(vec![(2, 2), (2, 1), (2, 0), (3, 0)].into());
             assert_eq!(*logic.snake(), vec![(2, 2), (2, 1), (2, 0), (3, 0)]);
 
             // Snake hits self.
-            logic.position_snake = vec![(2, 5), (3, 5), (4, 5), (5, 5)].into();
+            logic.set_snake(vec![(2, 5), (3, 5), (4, 5), (5, 5)].into());
             logic.direction = Direction::Left;
 
             assert!(logic.next_step().is_over());
 
             // Snake hits wall.
-            logic.position_snake = vec![(0, 3), (0, 2), (0, 1), (0, 0)].into();
+            logic.set_snake(vec![(0, 3), (0, 2), (0, 1), (0, 0)].into());
             logic.direction = Direction::Left;
 
             assert!(logic.next_step().is_over());
 
             // Normal
-            logic.position_snake = vec![(4, 1), (3, 1), (2, 1), (1, 1)].into();
+            logic.set_snake(vec![(4, 1), (3, 1), (2, 1), (1, 1)].into());
             logic.direction = Direction::Left;
 
             assert!(!logic.next_step().is_over());
@@ -555,12 +792,50 @@ mod tests {
         }
     }
 
+    #[test]
+    fn next_step_wrap_around() {
+        let mut logic = SnakeLogic::new(5, 5).unwrap();
+        logic.set_wall_mode(WallMode::WrapAround);
+        logic.set_food(vec![(2, 2)]);
+
+        logic.direction = Direction::Right;
+        logic.set_snake(vec![(3, 0), (4, 0)].into());
+        assert!(!logic.next_step().is_over());
+        assert_eq!(*logic.snake(), [(4, 0), (0, 0)]);
+
+        logic.direction = Direction::Left;
+        logic.set_snake(vec![(1, 4), (0, 4)].into());
+        assert!(!logic.next_step().is_over());
+        assert_eq!(*logic.snake(), [(0, 4), (4, 4)]);
+
+        logic.direction = Direction::Up;
+        logic.set_snake(vec![(4, 1), (4, 0)].into());
+        assert!(!logic.next_step().is_over());
+        assert_eq!(*logic.snake(), [(4, 0), (4, 4)]);
+
+        logic.direction = Direction::Down;
+        logic.set_snake(vec![(0, 3), (0, 4)].into());
+        assert!(!logic.next_step().is_over());
+        assert_eq!(*logic.snake(), [(0, 4), (0, 0)]);
+
+        // Wrapping around into your own tail is still game over.
+        logic.direction = Direction::Right;
+        logic.set_snake(vec![(1, 1), (0, 1), (4, 2), (4, 1)].into());
+        assert!(logic.next_step().is_over());
+
+        // Solid walls still end the game.
+        logic.set_wall_mode(WallMode::Solid);
+        logic.direction = Direction::Right;
+        logic.set_snake(vec![(3, 0), (4, 0)].into());
+        assert!(logic.next_step().is_over());
+    }
+
     #[test]
     fn food_test() {
-        let logic = SnakeLogic::new(10, 10).unwrap();
+        let mut logic = SnakeLogic::new(10, 10).unwrap();
 
         for _i in 0..50 {
-            let food = logic.generate_food();
+            let food = logic.generate_food().unwrap();
             assert!(
                 !logic.snake().contains(&food),
                 "Food {:?} was found in snake: {:?}",
@@ -582,6 +857,247 @@ mod tests {
         }
     }
 
+    #[test]
+    fn obstacles() {
+        assert!(SnakeLogic::with_obstacles(5, 5, [(5, 0)].into(), 0).is_none());
+        let all_but_one: BTreeSet<(usize, usize)> = (0..5)
+            .flat_map(|x| (0..5).map(move |y| (x, y)))
+            .skip(1)
+            .collect();
+        assert!(SnakeLogic::with_obstacles(5, 5, all_but_one, 0).is_none());
+
+        // Everything but the first two columns is blocked.
+        let obstacles: BTreeSet<(usize, usize)> =
+            (2..5).flat_map(|x| (0..5).map(move |y| (x, y))).collect();
+        for seed in 0..50 {
+            let mut logic = SnakeLogic::with_obstacles(5, 5, obstacles.clone(), seed).unwrap();
+            assert_eq!(*logic.obstacles(), obstacles);
+            assert!(!obstacles.contains(logic.snake().back().unwrap()));
+            assert!(
+                food_squares(&logic)
+                    .iter()
+                    .all(|food| !obstacles.contains(food))
+            );
+            assert!(!obstacles.contains(&logic.generate_food().unwrap()));
+        }
+
+        // Running into an obstacle is game over.
+        let mut logic = SnakeLogic::with_obstacles(5, 5, obstacles, 0).unwrap();
+        logic.set_snake(vec![(1, 2)].into());
+        logic.set_food(vec![(0, 0)]);
+        logic.direction = Direction::Right;
+        assert!(logic.next_step().is_over());
+    }
+
+    fn food_squares(logic: &SnakeLogic) -> Vec<(usize, usize)> {
+        logic.food().iter().map(|food| food.position()).collect()
+    }
+
+    #[test]
+    fn multiple_food() {
+        let mut logic = SnakeLogic::with_seed(10, 10, 5).unwrap();
+        logic.set_snake(vec![(0, 0), (1, 0)].into());
+        logic.set_food(vec![(2, 0), (0, 0), (5, 5)]);
+        assert_eq!(food_squares(&logic), [(2, 0), (5, 5)]);
+
+        logic.set_food_count(5);
+        assert_eq!(logic.food_count(), 5);
+        let squares = food_squares(&logic);
+        assert_eq!(squares.len(), 5);
+        assert_eq!(squares.iter().collect::<BTreeSet<_>>().len(), 5);
+        assert!(squares.iter().all(|food| !logic.snake().contains(food)));
+
+        // Eating one food item only respawns that one.
+        logic.direction = Direction::Right;
+        assert_eq!(logic.next_step(), GameResult::NoOp);
+        let squares = food_squares(&logic);
+        assert_eq!(squares.len(), 5);
+        assert!(!squares.contains(&(2, 0)));
+        assert!(squares.contains(&(5, 5)));
+
+        logic.set_food_count(2);
+        assert_eq!(logic.food().len(), 2);
+        logic.set_food_count(0);
+        assert_eq!(logic.food_count(), 1);
+        assert_eq!(logic.food().len(), 1);
+    }
+
+    #[test]
+    fn food_kinds() {
+        let mut logic = SnakeLogic::with_seed(10, 10, 5).unwrap();
+        logic.set_snake(vec![(0, 0), (1, 0), (2, 0), (3, 0), (4, 0)].into());
+        logic.set_food(vec![
+            Food::new((5, 0), FoodKind::Shrink),
+            Food::new((9, 9), FoodKind::Bonus),
+        ]);
+        logic.direction = Direction::Right;
+        assert_eq!(logic.next_step(), GameResult::NoOp);
+        assert_eq!(*logic.snake(), [(4, 0), (5, 0)]);
+        let events = logic.take_events();
+        assert!(events.contains(&GameEvent::FoodEaten {
+            position: (5, 0),
+            kind: FoodKind::Shrink,
+            new_length: 2
+        }));
+        assert!(events.contains(&GameEvent::Shrank { length: 2 }));
+        assert_eq!(logic.amount_of_growth, 0);
+
+        // Bonus food disappears if it is not eaten in time.
+        // The first step already counted down one tick.
+        logic.direction = Direction::Down;
+        logic.wall_mode = WallMode::WrapAround;
+        for _ in 2..BONUS_FOOD_TICKS {
+            assert_eq!(logic.next_step(), GameResult::NoOp);
+        }
+        assert!(logic.food().iter().any(|food| food.position() == (9, 9)));
+        let _ = logic.take_events();
+        assert_eq!(logic.next_step(), GameResult::NoOp);
+        assert!(logic.take_events().contains(&GameEvent::FoodExpired {
+            position: (9, 9),
+            kind: FoodKind::Bonus
+        }));
+        assert_eq!(logic.food().len(), 1);
+        assert_ne!(logic.food()[0].position(), (9, 9));
+    }
+
+    #[test]
+    fn same_seed_same_game() {
+        let mut first = SnakeLogic::with_seed(25, 25, 42).unwrap();
+        let mut second = SnakeLogic::with_seed(25, 25, 42).unwrap();
+        assert_eq!(first.seed(), 42);
+        assert_eq!(first, second);
+        for _ in 0..100 {
+            assert_eq!(first.generate_food(), second.generate_food());
+        }
+
+        let mut other = SnakeLogic::with_seed(25, 25, 43).unwrap();
+        let first_foods: Vec<_> = (0..10).map(|_| first.generate_food()).collect();
+        let other_foods: Vec<_> = (0..10).map(|_| other.generate_food()).collect();
+        assert_ne!(first_foods, other_foods);
+    }
+
+    #[test]
+    fn board_cleared() {
+        let mut logic = SnakeLogic::new(5, 5).unwrap();
+        // The snake covers every square but (4, 4) and still has to grow.
+        let mut snake: Vec<(usize, usize)> = Vec::new();
+        for y in 0..5 {
+            for x in 0..5 {
+                let x = if y % 2 == 0 { x } else { 4 - x };
+                snake.push((x, y));
+            }
+        }
+        snake.pop();
+        logic.set_snake(snake.into());
+        logic.set_food(vec![(4, 4)]);
+        logic.amount_of_growth = 1;
+        logic.direction = Direction::Right;
+        assert_eq!(food_squares(&logic), [(4, 4)]);
+        assert_eq!(logic.generate_food(), None);
+
+        let result = logic.next_step();
+        assert!(result.is_over());
+        assert!(result.is_victory());
+        assert_eq!(result, GameResult::BoardCleared);
+        assert_eq!(logic.snake().len(), 25);
+        assert_eq!(logic.generate_food(), None);
+        assert_eq!(
+            logic.take_events().last(),
+            Some(&GameEvent::BoardCleared { length: 25 })
+        );
+    }
+
+    #[test]
+    fn food_spawns_covered() {
+        let level: crate::level::Level = "#######\n\
+                                           #F...F#\n\
+                                           #.....#\n\
+                                           #..S..#\n\
+                                           #######\n"
+            .parse()
+            .unwrap();
+        let mut logic = SnakeLogic::from_level(&level, 0);
+        logic.set_food_count(3);
+        // Only two spawns, so only two food items.
+        let mut squares = food_squares(&logic);
+        squares.sort();
+        assert_eq!(squares, [(1, 1), (5, 1)]);
+
+        logic.set_snake(
+            vec![
+                (1usize, 1usize),
+                (1, 2),
+                (2, 2),
+                (3, 2),
+                (4, 2),
+                (5, 2),
+                (5, 1),
+            ]
+            .into(),
+        );
+        assert!(logic.food().is_empty());
+        assert_eq!(logic.generate_food(), None);
+    }
+
+    #[test]
+    fn events() {
+        let mut logic = SnakeLogic::new(10, 10).unwrap();
+        logic.growth_per_food = 1;
+        logic.set_snake(vec![(0, 0)].into());
+        logic.set_food(vec![(1, 0)]);
+
+        // Not moving does not give any events.
+        assert!(!logic.next_step().is_over());
+        assert_eq!(logic.take_events(), []);
+
+        logic.direction = Direction::Right;
+        assert!(!logic.next_step().is_over());
+        assert_eq!(
+            logic.take_events(),
+            [
+                GameEvent::Moved { head: (1, 0) },
+                GameEvent::FoodEaten {
+                    position: (1, 0),
+                    kind: FoodKind::Normal,
+                    new_length: 1
+                }
+            ]
+        );
+        assert!(logic.take_events().is_empty());
+
+        logic.set_food(vec![(9, 9)]);
+        assert!(!logic.next_step().is_over());
+        assert_eq!(
+            logic.take_events(),
+            [
+                GameEvent::Grew { length: 2 },
+                GameEvent::Moved { head: (2, 0) },
+                GameEvent::GrowthFinished { length: 2 }
+            ]
+        );
+
+        logic.direction = Direction::Up;
+        assert!(logic.next_step().is_over());
+        assert_eq!(
+            logic.take_events(),
+            [GameEvent::Died {
+                cause: DeathCause::Wall,
+                cell: (2, 0)
+            }]
+        );
+
+        logic.set_snake(vec![(2, 1), (2, 0), (1, 0), (1, 1)].into());
+        logic.direction = Direction::Right;
+        assert!(logic.next_step().is_over());
+        assert_eq!(
+            logic.take_events(),
+            [GameEvent::Died {
+                cause: DeathCause::Snake,
+                cell: (2, 1)
+            }]
+        );
+    }
+
     #[test]
     fn change_direction() {
         // Initializing the logic state.
@@ -596,23 +1112,62 @@ mod tests {
         assert_eq!(logic.direction(), Direction::Right);
         // Checking for lock
         assert!(!logic.can_change_direction);
-        // Changing in lock (should not work)
+        // Changing in lock (should be queued)
         logic.change_direction(Direction::Up);
-        // Checking that lock had prevented change
+        // Checking that lock had prevented change for this step
         assert_eq!(logic.direction(), Direction::Right);
-        // Using the GameResult (must_use) and freeing the lock
-        logic.position_snake = [(0, 0)].into();
+        assert_eq!(logic.queued_directions, [Direction::Up]);
+        // Using the GameResult (must_use), the queued direction is taken for the next step
+        logic.set_snake([(0, 1)].into());
         assert!(!logic.next_step().is_over());
+        assert_eq!(*logic.snake(), [(1, 1)]);
+        assert_eq!(logic.direction(), Direction::Up);
+        assert!(!logic.can_change_direction);
+        assert!(logic.queued_directions.is_empty());
+        // Freeing the lock
+        assert!(!logic.next_step().is_over());
+        assert_eq!(*logic.snake(), [(1, 0)]);
         // Checking that lock is removed
         assert!(logic.can_change_direction);
         // Doing an invalid move
-        logic.change_direction(Direction::Left);
+        logic.change_direction(Direction::Down);
         // Checking you cannot go front then back
-        assert_eq!(logic.direction(), Direction::Right);
+        assert_eq!(logic.direction(), Direction::Up);
         // Trying to stop moving
         logic.change_direction(Direction::None);
         // Checking you cannot stop moving
-        assert_eq!(logic.direction(), Direction::Right)
+        assert_eq!(logic.direction(), Direction::Up)
+    }
+
+    #[test]
+    fn queued_directions() {
+        let mut logic = SnakeLogic::new(25, 25).unwrap();
+        logic.set_snake([(10, 10)].into());
+        logic.set_food(vec![(0, 0)]);
+        logic.direction = Direction::Right;
+
+        // A quick U-turn within one step: up, then left.
+        logic.change_direction(Direction::Up);
+        logic.change_direction(Direction::Left);
+        assert!(!logic.next_step().is_over());
+        assert_eq!(*logic.snake(), [(10, 9)]);
+        assert!(!logic.next_step().is_over());
+        assert_eq!(*logic.snake(), [(9, 9)]);
+
+        // Reversals are checked against the direction that is used next, not the last one moved in.
+        logic.change_direction(Direction::Down);
+        logic.change_direction(Direction::Up);
+        assert!(logic.queued_directions.is_empty());
+        assert_eq!(logic.direction(), Direction::Down);
+        assert!(!logic.next_step().is_over());
+
+        // The queue is bounded.
+        logic.change_direction(Direction::Left);
+        logic.change_direction(Direction::Up);
+        logic.change_direction(Direction::Right);
+        logic.change_direction(Direction::Down);
+        assert_eq!(logic.direction(), Direction::Left);
+        assert_eq!(logic.queued_directions, [Direction::Up, Direction::Right]);
     }
 
     #[test]
@@ -627,7 +1182,7 @@ mod tests {
             let mut logic = SnakeLogic::new(24, 24).unwrap();
             assert_eq!(logic.new_head(), (logic.snake().back()).copied());
             logic.direction = Direction::Up;
-            logic.position_snake = vec![(1, 1)].into();
+            logic.set_snake(vec![(1, 1)].into());
 
             assert_eq!(
                 logic.new_head().unwrap(),
@@ -643,7 +1198,7 @@ mod tests {
         {
             let mut logic = SnakeLogic::new(25, 25).unwrap();
             logic.direction = Direction::Right;
-            logic.position_snake = vec![(1, 1)].into();
+            logic.set_snake(vec![(1, 1)].into());
 
             assert_eq!(
                 logic.new_head().unwrap(),
@@ -659,7 +1214,7 @@ mod tests {
         {
             let mut logic = SnakeLogic::new(25, 25).unwrap();
             logic.direction = Direction::Left;
-            logic.position_snake = vec![(1, 1)].into();
+            logic.set_snake(vec![(1, 1)].into());
 
             assert_eq!(
                 logic.new_head().unwrap(),
@@ -675,7 +1230,7 @@ mod tests {
         {
             let mut logic = SnakeLogic::new(25, 25).unwrap();
             logic.direction = Direction::Down;
-            logic.position_snake = vec![(1, 1)].into();
+            logic.set_snake(vec![(1, 1)].into());
 
             assert_eq!(
                 logic.new_head().unwrap(),
@@ -693,8 +1248,8 @@ mod tests {
         {
             let mut logic = SnakeLogic::new(5, 5).unwrap();
             logic.direction = Direction::Right;
-            logic.position_food = (4, 4);
-            logic.position_snake = vec![(0usize, 0usize)].into();
+            logic.set_food(vec![(4, 4)]);
+            logic.set_snake(vec![(0usize, 0usize)].into());
             assert!(!logic.next_step().is_over());
             assert!(!logic.next_step().is_over());
             assert!(!logic.next_step().is_over());
@@ -704,8 +1259,8 @@ mod tests {
         {
             let mut logic = SnakeLogic::new(5, 5).unwrap();
             logic.direction = Direction::Left;
-            logic.position_food = (1, 1);
-            logic.position_snake = vec![(4usize, 4usize)].into();
+            logic.set_food(vec![(1, 1)]);
+            logic.set_snake(vec![(4usize, 4usize)].into());
             assert!(!logic.next_step().is_over());
             assert!(!logic.next_step().is_over());
             assert!(!logic.next_step().is_over());
@@ -715,8 +1270,8 @@ mod tests {
         {
             let mut logic = SnakeLogic::new(5, 5).unwrap();
             logic.direction = Direction::Down;
-            logic.position_food = (4, 4);
-            logic.position_snake = vec![(0usize, 0usize)].into();
+            logic.set_food(vec![(4, 4)]);
+            logic.set_snake(vec![(0usize, 0usize)].into());
             assert!(!logic.next_step().is_over());
             assert!(!logic.next_step().is_over());
             assert!(!logic.next_step().is_over());
@@ -726,8 +1281,8 @@ mod tests {
         {
             let mut logic = SnakeLogic::new(5, 5).unwrap();
             logic.direction = Direction::Up;
-            logic.position_food = (1, 1);
-            logic.position_snake = vec![(4usize, 4usize)].into();
+            logic.set_food(vec![(1, 1)]);
+            logic.set_snake(vec![(4usize, 4usize)].into());
             assert!(!logic.next_step().is_over());
             assert!(!logic.next_step().is_over());
             assert!(!logic.next_step().is_over());
@@ -737,8 +1292,8 @@ mod tests {
         {
             let mut logic = SnakeLogic::new(5, 5).unwrap();
             assert_eq!(logic.direction(), Direction::None);
-            logic.position_food = (1, 1);
-            logic.position_snake = vec![(4usize, 4usize)].into();
+            logic.set_food(vec![(1, 1)]);
+            logic.set_snake(vec![(4usize, 4usize)].into());
             for _ in 0..1000 {
                 assert!(!logic.next_step().is_over())
             }
@@ -0,0 +1,359 @@
+//! Color themes: the colors the board, the snake, the food and the text are drawn with.
+//!
+//! The built-in themes are in `data/themes.toml`. Themes in a file with the same layout in the
+//! user's config directory are added to them, and replace a built-in theme with the same name.
+//! Colors are `[red, green, blue]` and every color that is left out is the one of the
+//! [`DEFAULT_THEME`]:
+//!
+//! ```toml
+//! [[theme]]
+//! name = "Mint"
+//! head = [170, 255, 210]
+//! body = [60, 200, 140]
+//! body_tail = [20, 90, 70]
+//!
+//! [theme.food]
+//! normal = [255, 120, 120]
+//! ```
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::food::FoodKind;
+
+/// The theme chosen when the settings name none that exists.
+pub const DEFAULT_THEME: &str = "Classic";
+
+const BUILTIN_THEMES: &str = include_str!("../data/themes.toml");
+
+/// A color as red, green and blue.
+pub type Rgb = (u8, u8, u8);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub name: String,
+    /// Fills the whole frame behind everything else.
+    pub background: Rgb,
+    /// The lines between the squares of the board. Not drawn if it is the background color.
+    pub grid: Rgb,
+    pub head: Rgb,
+    /// The body right behind the head. It fades to [`Theme::body_tail`] towards the tail.
+    pub body: Rgb,
+    pub body_tail: Rgb,
+    pub food: FoodColors,
+    /// The obstacles and the border around the board.
+    pub walls: Rgb,
+    pub text: Rgb,
+    /// The selected menu entry and other text that should stand out.
+    pub highlight: Rgb,
+    /// Text about something good, like clearing the board or a replay that plays the same.
+    pub success: Rgb,
+    /// Text about something bad, like the game being over or a replay that doesn't play the same.
+    pub warning: Rgb,
+    /// Text that tells how the game is going, like its speed.
+    pub info: Rgb,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+/// The color of every kind of food.
+pub struct FoodColors {
+    pub normal: Rgb,
+    pub bonus: Rgb,
+    pub shrink: Rgb,
+    pub speed_up: Rgb,
+    pub slow_down: Rgb,
+    pub golden: Rgb,
+}
+
+impl Theme {
+    pub fn food(&self, kind: FoodKind) -> Rgb {
+        match kind {
+            FoodKind::Normal => self.food.normal,
+            FoodKind::Bonus => self.food.bonus,
+            FoodKind::Shrink => self.food.shrink,
+            FoodKind::SpeedUp => self.food.speed_up,
+            FoodKind::SlowDown => self.food.slow_down,
+            FoodKind::Golden => self.food.golden,
+        }
+    }
+
+    /// The color of the body square `index` squares behind the head, for a body of `length`
+    /// squares without the head.
+    pub fn body(&self, index: usize, length: usize) -> Rgb {
+        let t = if length <= 1 {
+            0.
+        } else {
+            index.min(length - 1) as f32 / (length - 1) as f32
+        };
+        let mix = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * t).round() as u8;
+        (
+            mix(self.body.0, self.body_tail.0),
+            mix(self.body.1, self.body_tail.1),
+            mix(self.body.2, self.body_tail.2),
+        )
+    }
+
+    /// Gives [`true`] if the grid lines should be drawn.
+    pub fn has_grid(&self) -> bool {
+        self.grid != self.background
+    }
+}
+
+impl Default for Theme {
+    /// The classic green snake on black.
+    fn default() -> Self {
+        Theme {
+            name: DEFAULT_THEME.to_string(),
+            background: (0, 0, 0),
+            grid: (0, 0, 0),
+            head: (0, 255, 0),
+            body: (0, 255, 0),
+            body_tail: (0, 255, 0),
+            food: FoodColors::default(),
+            walls: (128, 128, 128),
+            text: (255, 255, 255),
+            highlight: (255, 255, 0),
+            success: (0, 255, 0),
+            warning: (255, 0, 0),
+            info: (0, 191, 255),
+        }
+    }
+}
+
+impl Default for FoodColors {
+    fn default() -> Self {
+        FoodColors {
+            normal: (255, 0, 0),
+            bonus: (255, 140, 0),
+            shrink: (160, 32, 240),
+            speed_up: (0, 191, 255),
+            slow_down: (65, 105, 225),
+            golden: (255, 215, 0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// All themes the player can choose from, in the order they are shown in the settings.
+pub struct Themes {
+    #[serde(rename = "theme", default)]
+    themes: Vec<Theme>,
+}
+
+impl Themes {
+    pub fn builtin() -> Themes {
+        BUILTIN_THEMES
+            .parse()
+            .expect("The built-in themes are valid")
+    }
+
+    /// Where the user's themes are, or [`None`] if the platform has no config directory (like the web).
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("snake").join("themes.toml"))
+    }
+
+    /// Loads the built-in themes together with the themes in `path`. A missing file gives only
+    /// the built-in themes.
+    pub fn load(path: impl AsRef<Path>) -> Result<Themes, ThemeError> {
+        let mut themes = Themes::builtin();
+        let user_themes: Themes = match std::fs::read_to_string(path) {
+            Ok(text) => text.parse()?,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(themes),
+            Err(error) => return Err(ThemeError::Io(error)),
+        };
+        for theme in user_themes.themes {
+            match themes
+                .themes
+                .iter_mut()
+                .find(|other| other.name == theme.name)
+            {
+                Some(builtin) => *builtin = theme,
+                None => themes.themes.push(theme),
+            }
+        }
+        Ok(themes)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Theme> {
+        self.themes.iter()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Theme> {
+        self.themes.iter().find(|theme| theme.name == name)
+    }
+
+    /// The theme called `name`, or the first one if there is none.
+    pub fn get_or_first(&self, name: &str) -> &Theme {
+        self.get(name).unwrap_or(&self.themes[0])
+    }
+
+    pub fn next_name(&self, name: &str) -> String {
+        let index = self.position(name);
+        self.themes[(index + 1) % self.themes.len()].name.clone()
+    }
+
+    pub fn previous_name(&self, name: &str) -> String {
+        let index = self.position(name);
+        self.themes[(index + self.themes.len() - 1) % self.themes.len()]
+            .name
+            .clone()
+    }
+
+    /// Gives `name` back if there is such a theme, or else the name of a default one.
+    pub fn known_name(&self, name: &str) -> String {
+        if self.get(name).is_some() {
+            name.to_string()
+        } else if self.get(DEFAULT_THEME).is_some() {
+            DEFAULT_THEME.to_string()
+        } else {
+            self.themes[0].name.clone()
+        }
+    }
+
+    fn position(&self, name: &str) -> usize {
+        self.themes
+            .iter()
+            .position(|theme| theme.name == name)
+            .unwrap_or_default()
+    }
+}
+
+impl Default for Themes {
+    fn default() -> Self {
+        Themes::builtin()
+    }
+}
+
+impl FromStr for Themes {
+    type Err = ThemeError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let themes: Themes = toml::from_str(text).map_err(ThemeError::Invalid)?;
+        if themes.themes.is_empty() {
+            return Err(ThemeError::Empty);
+        }
+        for (index, theme) in themes.themes.iter().enumerate() {
+            if theme.name.is_empty() || theme.name.contains(char::is_whitespace) {
+                return Err(ThemeError::InvalidName(theme.name.clone()));
+            }
+            if themes.themes[..index]
+                .iter()
+                .any(|other| other.name == theme.name)
+            {
+                return Err(ThemeError::DuplicateName(theme.name.clone()));
+            }
+        }
+        Ok(themes)
+    }
+}
+
+#[derive(Debug)]
+/// This enum tells why themes could not be loaded.
+pub enum ThemeError {
+    Io(std::io::Error),
+    Invalid(toml::de::Error),
+    /// The file has no themes at all.
+    Empty,
+    /// The theme has no name or a name with spaces.
+    InvalidName(String),
+    DuplicateName(String),
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThemeError::Io(error) => write!(f, "{error}"),
+            ThemeError::Invalid(error) => write!(f, "the themes are invalid: {error}"),
+            ThemeError::Empty => write!(f, "there are no themes"),
+            ThemeError::InvalidName(name) => write!(f, "the theme name {name:?} is invalid"),
+            ThemeError::DuplicateName(name) => {
+                write!(f, "there is more than one theme called {name:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ThemeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin() {
+        let themes = Themes::builtin();
+        assert_eq!(themes.get(DEFAULT_THEME), Some(&Theme::default()));
+        assert!(themes.get("HighContrast").is_some());
+        assert!(themes.get("ColorblindSafe").is_some());
+
+        assert_eq!(
+            themes.next_name(DEFAULT_THEME),
+            themes.iter().nth(1).unwrap().name
+        );
+        assert_eq!(
+            themes.previous_name(&themes.next_name(DEFAULT_THEME)),
+            DEFAULT_THEME
+        );
+        assert_eq!(themes.known_name("Gone"), DEFAULT_THEME);
+        assert_eq!(themes.get_or_first("Gone").name, DEFAULT_THEME);
+    }
+
+    #[test]
+    fn body_gradient() {
+        let theme = Theme {
+            body: (0, 200, 0),
+            body_tail: (100, 0, 0),
+            ..Theme::default()
+        };
+        assert_eq!(theme.body(0, 5), (0, 200, 0));
+        assert_eq!(theme.body(2, 5), (50, 100, 0));
+        assert_eq!(theme.body(4, 5), (100, 0, 0));
+        assert_eq!(theme.body(0, 1), (0, 200, 0));
+        assert_eq!(theme.body(0, 0), (0, 200, 0));
+    }
+
+    #[test]
+    fn user_file() {
+        let path = std::env::temp_dir().join(format!("snake-themes-{}.toml", std::process::id()));
+        std::fs::write(
+            &path,
+            "[[theme]]\nname = \"Mint\"\nhead = [1, 2, 3]\n\n[theme.food]\nnormal = [4, 5, 6]\n\n\
+             [[theme]]\nname = \"Classic\"\nbackground = [10, 10, 10]\n",
+        )
+        .unwrap();
+        let themes = Themes::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let mint = themes.get("Mint").unwrap();
+        assert_eq!(mint.head, (1, 2, 3));
+        assert_eq!(mint.food(FoodKind::Normal), (4, 5, 6));
+        assert_eq!(mint.food(FoodKind::Bonus), FoodColors::default().bonus);
+        assert_eq!(mint.text, Theme::default().text);
+        assert_eq!(mint.warning, Theme::default().warning);
+        assert_eq!(themes.get(DEFAULT_THEME).unwrap().background, (10, 10, 10));
+        assert_eq!(themes.iter().count(), Themes::builtin().iter().count() + 1);
+
+        assert_eq!(
+            Themes::load(std::env::temp_dir().join("snake-no-such-themes.toml")).unwrap(),
+            Themes::builtin()
+        );
+        assert!(matches!("".parse::<Themes>(), Err(ThemeError::Empty)));
+        assert!(matches!(
+            "[[theme]]\nname = \"Two words\"".parse::<Themes>(),
+            Err(ThemeError::InvalidName(_))
+        ));
+        assert!(matches!(
+            "[[theme]]\nname = \"A\"\n[[theme]]\nname = \"A\"".parse::<Themes>(),
+            Err(ThemeError::DuplicateName(_))
+        ));
+        assert!(matches!(
+            "[[theme]]\nname = \"A\"\nhead = [300, 0, 0]".parse::<Themes>(),
+            Err(ThemeError::Invalid(_))
+        ));
+    }
+}
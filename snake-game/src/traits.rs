@@ -1,3 +1,14 @@
+/// A color as red, green, blue and alpha. An alpha of 255 is opaque and 0 is invisible.
+pub type Rgba = (u8, u8, u8, u8);
+
+/// Circles drawn by the default [`DrawableOn::fill_circle`] are made of at most this many
+/// rectangles.
+pub const CIRCLE_STRIPS: usize = 16;
+
+/// Something the game can be drawn on.
+///
+/// Only text and rectangles have to be implemented. Every other shape has a default built from
+/// rectangles, which frontends can replace with the real thing.
 pub trait DrawableOn {
     fn draw_text(&mut self, text: &str, color_rgb: (u8, u8, u8), x: usize, y: usize, size: f32);
     fn height(&self) -> usize;
@@ -8,4 +19,227 @@ pub trait DrawableOn {
         color_rgb: (u8, u8, u8),
         top_left: (usize, usize),
     );
+
+    /// Like [`DrawableOn::fill_rectangle`], but the color can be see-through.
+    /// The default draws it opaque, or not at all if it is invisible.
+    fn fill_rectangle_rgba(
+        &mut self,
+        size: (usize, usize),
+        color_rgba: Rgba,
+        top_left: (usize, usize),
+    ) {
+        if color_rgba.3 > 0 {
+            let (red, green, blue, _) = color_rgba;
+            self.fill_rectangle(size, (red, green, blue), top_left);
+        }
+    }
+
+    /// Fills a circle. The default stacks rectangles of the width of the circle at their height.
+    fn fill_circle(&mut self, center: (f32, f32), radius: f32, color_rgba: Rgba) {
+        if radius <= 0. {
+            return;
+        }
+        let strips = (radius.ceil() as usize).clamp(1, CIRCLE_STRIPS / 2) * 2;
+        let strip_height = 2. * radius / strips as f32;
+        for i in 0..strips {
+            let top = center.1 - radius + i as f32 * strip_height;
+            let middle = top + strip_height / 2. - center.1;
+            let half_width = (radius * radius - middle * middle).max(0.).sqrt();
+            let left = pixel(center.0 - half_width);
+            let top_pixel = pixel(top);
+            let size = (
+                pixel(center.0 + half_width).saturating_sub(left),
+                pixel(top + strip_height).saturating_sub(top_pixel),
+            );
+            if size.0 > 0 && size.1 > 0 {
+                self.fill_rectangle_rgba(size, color_rgba, (left, top_pixel));
+            }
+        }
+    }
+
+    /// Fills a rectangle whose corners are rounded with `radius`.
+    /// The default is a cross of two rectangles with a circle in every corner.
+    fn fill_rounded_rectangle(
+        &mut self,
+        size: (usize, usize),
+        radius: f32,
+        color_rgba: Rgba,
+        top_left: (usize, usize),
+    ) {
+        let radius = radius.min(size.0 as f32 / 2.).min(size.1 as f32 / 2.);
+        let inset = radius.floor() as usize;
+        if inset == 0 {
+            return self.fill_rectangle_rgba(size, color_rgba, top_left);
+        }
+        let (left, top) = top_left;
+        let (width, height) = size;
+        self.fill_rectangle_rgba((width, height - 2 * inset), color_rgba, (left, top + inset));
+        self.fill_rectangle_rgba((width - 2 * inset, height), color_rgba, (left + inset, top));
+        let (near_x, near_y) = ((left + inset) as f32, (top + inset) as f32);
+        let (far_x, far_y) = ((left + width - inset) as f32, (top + height - inset) as f32);
+        for center in [
+            (near_x, near_y),
+            (far_x, near_y),
+            (near_x, far_y),
+            (far_x, far_y),
+        ] {
+            self.fill_circle(center, inset as f32, color_rgba);
+        }
+    }
+
+    /// Draws a straight line `width` thick. The default draws a rectangle if the line is
+    /// horizontal or vertical, and a row of squares along it if not.
+    fn draw_line(&mut self, from: (f32, f32), to: (f32, f32), width: f32, color_rgba: Rgba) {
+        let half = width / 2.;
+        if from.0 == to.0 || from.1 == to.1 {
+            let left = pixel(from.0.min(to.0) - half);
+            let top = pixel(from.1.min(to.1) - half);
+            let size = (
+                pixel(from.0.max(to.0) + half).saturating_sub(left),
+                pixel(from.1.max(to.1) + half).saturating_sub(top),
+            );
+            return self.fill_rectangle_rgba(size, color_rgba, (left, top));
+        }
+        let length = ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt();
+        let steps = (length / half.max(0.5)).ceil() as usize;
+        let square = pixel(width).max(1);
+        for step in 0..=steps {
+            let t = step as f32 / steps as f32;
+            let x = from.0 + (to.0 - from.0) * t;
+            let y = from.1 + (to.1 - from.1) * t;
+            self.fill_rectangle_rgba(
+                (square, square),
+                color_rgba,
+                (pixel(x - half), pixel(y - half)),
+            );
+        }
+    }
+
+    /// Draws the sprite or image called `name`. The default, and frontends that don't have a
+    /// sprite with that name, fill the rectangle with `fallback_rgba` instead.
+    fn draw_sprite(
+        &mut self,
+        name: &str,
+        size: (usize, usize),
+        top_left: (usize, usize),
+        fallback_rgba: Rgba,
+    ) {
+        let _ = name;
+        self.fill_rectangle_rgba(size, fallback_rgba, top_left);
+    }
+}
+
+/// Rounds a position to the nearest pixel that is on the frame.
+fn pixel(value: f32) -> usize {
+    value.round().max(0.) as usize
+}
+
+#[cfg(test)]
+pub(crate) mod recording {
+    use super::DrawableOn;
+
+    /// The size, color and top left corner of a rectangle.
+    pub(crate) type Drawn = ((usize, usize), (u8, u8, u8), (usize, usize));
+
+    /// A frame that only records the rectangles drawn on it, so the default shapes and
+    /// everything drawn with them can be checked in tests.
+    #[derive(Default)]
+    pub(crate) struct RecordingFrame {
+        pub(crate) rectangles: Vec<Drawn>,
+    }
+
+    impl RecordingFrame {
+        /// Gives [`true`] if every rectangle is within `left_top` and `right_bottom`.
+        pub(crate) fn within(
+            &self,
+            (left, top): (usize, usize),
+            (right, bottom): (usize, usize),
+        ) -> bool {
+            self.rectangles.iter().all(|&((width, height), _, (x, y))| {
+                x >= left && y >= top && x + width <= right && y + height <= bottom
+            })
+        }
+
+        /// The top left and bottom right corner around all rectangles of `color`.
+        pub(crate) fn bounds(&self, color: (u8, u8, u8)) -> ((usize, usize), (usize, usize)) {
+            let rectangles = self
+                .rectangles
+                .iter()
+                .filter(|(_, other, _)| *other == color);
+            rectangles.fold(
+                ((usize::MAX, usize::MAX), (0, 0)),
+                |((left, top), (right, bottom)), &((width, height), _, (x, y))| {
+                    (
+                        (left.min(x), top.min(y)),
+                        (right.max(x + width), bottom.max(y + height)),
+                    )
+                },
+            )
+        }
+    }
+
+    impl DrawableOn for RecordingFrame {
+        fn draw_text(&mut self, _: &str, _: (u8, u8, u8), _: usize, _: usize, _: f32) {}
+        fn height(&self) -> usize {
+            600
+        }
+        fn width(&self) -> usize {
+            1000
+        }
+        fn fill_rectangle(
+            &mut self,
+            size: (usize, usize),
+            color_rgb: (u8, u8, u8),
+            top_left: (usize, usize),
+        ) {
+            self.rectangles.push((size, color_rgb, top_left));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::recording::RecordingFrame;
+    use super::*;
+
+    #[test]
+    fn default_shapes() {
+        let red = (255, 0, 0);
+        let mut frame = RecordingFrame::default();
+        frame.fill_rectangle_rgba((5, 5), (255, 0, 0, 0), (0, 0));
+        assert!(frame.rectangles.is_empty());
+        frame.draw_sprite("head", (5, 6), (1, 2), (255, 0, 0, 128));
+        assert_eq!(frame.rectangles, vec![((5, 6), red, (1, 2))]);
+
+        let mut frame = RecordingFrame::default();
+        frame.fill_circle((50., 50.), 10., (255, 0, 0, 255));
+        assert_eq!(frame.rectangles.len(), CIRCLE_STRIPS);
+        assert!(frame.within((40, 40), (60, 60)));
+        let widest = frame
+            .rectangles
+            .iter()
+            .map(|((width, _), _, _)| *width)
+            .max()
+            .unwrap();
+        assert!(widest >= 18);
+
+        let mut frame = RecordingFrame::default();
+        frame.fill_rounded_rectangle((20, 10), 3., (255, 0, 0, 255), (10, 10));
+        assert!(frame.within((10, 10), (30, 20)));
+        assert!(
+            !frame
+                .rectangles
+                .iter()
+                .any(|&(_, _, top_left)| top_left == (10, 10))
+        );
+
+        let mut frame = RecordingFrame::default();
+        frame.draw_line((10., 20.), (30., 20.), 2., (255, 0, 0, 255));
+        assert_eq!(frame.rectangles, vec![((22, 2), red, (9, 19))]);
+
+        let mut frame = RecordingFrame::default();
+        frame.draw_line((10., 10.), (20., 20.), 2., (255, 0, 0, 255));
+        assert!(frame.within((9, 9), (21, 21)));
+        assert!(frame.rectangles.len() > 10);
+    }
 }
@@ -1,25 +1,47 @@
 use iced::keyboard::Key;
 use iced::widget::canvas::event::Status::{Captured, Ignored};
-use iced::widget::canvas::{Frame, Geometry, Text};
+use iced::widget::canvas::{Frame, Geometry, Image, Path, Stroke, Text};
+use iced::widget::image::Handle;
 use iced::{Color, Point, window};
-use iced::{Element, Fill, Font, Pixels, Rectangle, Renderer, Size, Subscription, Theme};
+use iced::{Element, Fill, Font, Pixels, Rectangle, Renderer, Size, Subscription, Task, Theme};
+use snake_game::clock::SystemClock;
 use snake_game::game_with_menu::GameWithMenu;
-use snake_game::traits::DrawableOn;
+use snake_game::layout::LINE_HEIGHT;
+use snake_game::traits::{DrawableOn, Rgba};
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use web_time::Instant;
 
 pub fn main() -> iced::Result {
     tracing_subscriber::fmt::init();
+    let mut game_with_menu = GameWithMenu::from_args(std::env::args().skip(1), SystemClock)
+        .unwrap_or_else(|error| {
+            eprintln!("{error}");
+            std::process::exit(2)
+        });
+    print_warnings(&mut game_with_menu);
     iced::application("Snake by Arnold Afach", SnakeGUI::update, SnakeGUI::view)
         .subscription(SnakeGUI::subscription)
         .theme(SnakeGUI::theme)
-        .run()
+        .run_with(move || (SnakeGUI::new(game_with_menu), Task::none()))
 }
 
+/// Tells the player about files the game could not load or save.
+fn print_warnings(game_with_menu: &mut GameWithMenu) {
+    for warning in game_with_menu.take_warnings() {
+        eprintln!("{warning}");
+    }
+}
+
+/// Every `NAME.png` in this directory next to the executable is drawn for the sprite called `NAME`.
+const SPRITE_DIR: &str = "sprites";
+
 struct SnakeGUI {
     system_cache: iced::widget::canvas::Cache,
     now: Instant,
     game_with_menu: Arc<Mutex<GameWithMenu>>,
+    sprites: HashMap<String, Handle>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -38,7 +60,9 @@ impl SnakeGUI {
         match message {
             Message::Tick(now) => {
                 self.now = now;
-                self.game_with_menu.lock().expect("Poisoned").update(now);
+                let mut game_with_menu = self.game_with_menu.lock().expect("Poisoned");
+                game_with_menu.tick();
+                print_warnings(&mut game_with_menu);
                 self.system_cache.clear();
             }
         }
@@ -57,7 +81,11 @@ impl SnakeGUI {
     }
 }
 
-struct IcedFrame<'a>(&'a mut Frame<Renderer>);
+struct IcedFrame<'a>(&'a mut Frame<Renderer>, &'a HashMap<String, Handle>);
+
+fn rgba_color((red, green, blue, alpha): Rgba) -> Color {
+    Color::from_rgba8(red, green, blue, alpha as f32 / 255.)
+}
 
 impl DrawableOn for IcedFrame<'_> {
     fn draw_text(&mut self, text: &str, color: (u8, u8, u8), x: usize, y: usize, size: f32) {
@@ -70,7 +98,7 @@ impl DrawableOn for IcedFrame<'_> {
             },
             color,
             size: Pixels(size),
-            line_height: iced::widget::text::LineHeight::Absolute(Pixels(20.)),
+            line_height: iced::widget::text::LineHeight::Relative(LINE_HEIGHT),
             font: Font {
                 family: iced::font::Family::SansSerif,
                 weight: iced::font::Weight::Black,
@@ -110,6 +138,68 @@ impl DrawableOn for IcedFrame<'_> {
             Color::from_rgb8(color_rgb.0, color_rgb.1, color_rgb.2),
         );
     }
+
+    fn fill_rectangle_rgba(
+        &mut self,
+        size: (usize, usize),
+        color_rgba: Rgba,
+        top_left: (usize, usize),
+    ) {
+        self.0.fill_rectangle(
+            Point::new(top_left.0 as f32, top_left.1 as f32),
+            Size::new(size.0 as f32, size.1 as f32),
+            rgba_color(color_rgba),
+        );
+    }
+
+    fn fill_circle(&mut self, center: (f32, f32), radius: f32, color_rgba: Rgba) {
+        let circle = Path::circle(Point::new(center.0, center.1), radius);
+        self.0.fill(&circle, rgba_color(color_rgba));
+    }
+
+    fn fill_rounded_rectangle(
+        &mut self,
+        size: (usize, usize),
+        radius: f32,
+        color_rgba: Rgba,
+        top_left: (usize, usize),
+    ) {
+        let rectangle = Path::rounded_rectangle(
+            Point::new(top_left.0 as f32, top_left.1 as f32),
+            Size::new(size.0 as f32, size.1 as f32),
+            radius.into(),
+        );
+        self.0.fill(&rectangle, rgba_color(color_rgba));
+    }
+
+    fn draw_line(&mut self, from: (f32, f32), to: (f32, f32), width: f32, color_rgba: Rgba) {
+        let line = Path::line(Point::new(from.0, from.1), Point::new(to.0, to.1));
+        self.0.stroke(
+            &line,
+            Stroke::default()
+                .with_color(rgba_color(color_rgba))
+                .with_width(width),
+        );
+    }
+
+    fn draw_sprite(
+        &mut self,
+        name: &str,
+        size: (usize, usize),
+        top_left: (usize, usize),
+        fallback_rgba: Rgba,
+    ) {
+        match self.1.get(name) {
+            Some(handle) => self.0.draw_image(
+                Rectangle::new(
+                    Point::new(top_left.0 as f32, top_left.1 as f32),
+                    Size::new(size.0 as f32, size.1 as f32),
+                ),
+                Image::new(handle.clone()),
+            ),
+            None => self.fill_rectangle_rgba(size, fallback_rgba, top_left),
+        }
+    }
 }
 
 impl<T: Default> iced::widget::canvas::Program<T> for SnakeGUI {
@@ -127,7 +217,7 @@ impl<T: Default> iced::widget::canvas::Program<T> for SnakeGUI {
             self.game_with_menu
                 .lock()
                 .expect("Poisoned")
-                .draw(&mut IcedFrame(frame));
+                .draw(&mut IcedFrame(frame, &self.sprites));
         });
 
         vec![my_snake]
@@ -191,12 +281,34 @@ impl<T: Default> iced::widget::canvas::Program<T> for SnakeGUI {
     }
 }
 
-impl Default for SnakeGUI {
-    fn default() -> Self {
+impl SnakeGUI {
+    fn new(game_with_menu: GameWithMenu) -> Self {
         Self {
             system_cache: Default::default(),
             now: Instant::now(),
-            game_with_menu: Arc::new(Mutex::new(GameWithMenu::default())),
+            game_with_menu: Arc::new(Mutex::new(game_with_menu)),
+            sprites: sprite_dir().map(load_sprites).unwrap_or_default(),
         }
     }
 }
+
+/// The [`SPRITE_DIR`] next to the executable, so the sprites are found from any working directory.
+fn sprite_dir() -> Option<PathBuf> {
+    Some(std::env::current_exe().ok()?.parent()?.join(SPRITE_DIR))
+}
+
+/// Finds the PNG images in `dir`, named after their file names without the extension.
+/// A missing directory just gives no sprites.
+fn load_sprites(dir: PathBuf) -> HashMap<String, Handle> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return HashMap::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "png"))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_str()?.to_string();
+            Some((name, Handle::from_path(path)))
+        })
+        .collect()
+}